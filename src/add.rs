@@ -0,0 +1,224 @@
+use crate::config::{BingoConfig, ExecutableType, Transaction};
+use crate::error::{BingoError, BingoResult};
+use crate::paths;
+use colored::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// What `cp`/`ln` do when a name is already registered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnConflict {
+    Skip,
+    Replace,
+    /// Register under the first free `name-2`, `name-3`, ...
+    Rename,
+    Fail,
+}
+
+impl OnConflict {
+    pub const NAMES: &'static [&'static str] = &["skip", "replace", "rename", "fail"];
+}
+
+impl From<&str> for OnConflict {
+    fn from(s: &str) -> Self {
+        match s {
+            "skip" => OnConflict::Skip,
+            "replace" => OnConflict::Replace,
+            "rename" => OnConflict::Rename,
+            _ => OnConflict::Fail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Added,
+    Replaced,
+    /// Added under another name because the wanted one was taken.
+    Renamed(String),
+    Skipped,
+}
+
+/// One file handled by `cp`/`ln`.
+pub struct Added {
+    pub path: PathBuf,
+    pub name: String,
+    pub outcome: Outcome,
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Regular files, or links to them, that someone may execute.
+fn is_executable_file(path: &Path) -> bool {
+    match path.metadata() {
+        Ok(meta) => meta.is_file() && meta.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+/// Executables in `dir`, sorted by name. Hidden entries are left out and
+/// linked directories are not followed. Names that are not UTF-8 cannot be
+/// registered, so they are skipped with a warning.
+fn dir_executables(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> BingoResult<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            let e = BingoError::FileSystemError(format!("{}: {}", dir.display(), err));
+            return Err(e);
+        }
+    };
+    let mut entries = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter(|entry| {
+            let utf8 = entry.file_name().to_str().is_some();
+            if !utf8 {
+                let path = entry.path();
+                eprintln!(
+                    "{} skipping {}, not valid UTF-8",
+                    "warning:".yellow(),
+                    path.display()
+                );
+            }
+            utf8
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir {
+            if recursive {
+                dir_executables(&path, recursive, files)?;
+            }
+        } else if is_executable_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The files named by the `cp`/`ln` arguments. A path is taken as is, so
+/// a wrong one is reported when adding it, while directories and glob
+/// patterns only contribute executable files.
+pub fn collect(inputs: &[String], recursive: bool) -> BingoResult<Vec<PathBuf>> {
+    let mut files = vec![];
+    for input in inputs {
+        let path = Path::new(input);
+        let before = files.len();
+        if path.is_dir() {
+            dir_executables(path, recursive, &mut files)?;
+        } else if is_glob(input) && !path.exists() {
+            let options = glob::MatchOptions {
+                require_literal_leading_dot: true,
+                ..Default::default()
+            };
+            let matches = match glob::glob_with(input, options) {
+                Ok(matches) => matches,
+                Err(_) => return Err(BingoError::InvalidPattern(input.to_string())),
+            };
+            for path in matches.filter_map(|m| m.ok()) {
+                if path.to_str().is_none() {
+                    eprintln!(
+                        "{} skipping {}, not valid UTF-8",
+                        "warning:".yellow(),
+                        path.display()
+                    );
+                } else if path.is_dir() {
+                    dir_executables(&path, recursive, &mut files)?;
+                } else if is_executable_file(&path) {
+                    files.push(path);
+                }
+            }
+        } else {
+            files.push(path.to_path_buf());
+        }
+        if files.len() == before {
+            eprintln!("{} no executables in {}", "warning:".yellow(), input);
+        }
+    }
+    // `bingo cp .` would otherwise record `/cwd/./tool`
+    let mut files = files
+        .iter()
+        .map(|file| paths::absolute(file))
+        .collect::<Vec<_>>();
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    if files.is_empty() {
+        return Err(BingoError::FileNotFound(inputs.join(" ")));
+    }
+    Ok(files)
+}
+
+/// A command name like `ls`, to be looked up in `PATH`.
+pub fn is_bare(input: &str) -> bool {
+    !input.contains('/') && !is_glob(input) && !Path::new(input).exists()
+}
+
+/// The executables called `name` in the directories of `path_var`, in
+/// order. The bin dir is left out, its entries are bingo's own.
+pub fn path_candidates(name: &str, path_var: &str, bin_dir: &str) -> Vec<PathBuf> {
+    let bin_dir = std::fs::canonicalize(bin_dir).ok();
+    let mut candidates = vec![];
+    for dir in path_var.split(':').filter(|dir| !dir.is_empty()) {
+        if bin_dir.is_some() && std::fs::canonicalize(dir).ok() == bin_dir {
+            continue;
+        }
+        let path = Path::new(dir).join(name);
+        if is_executable_file(&path) && !candidates.contains(&path) {
+            candidates.push(path);
+        }
+    }
+    candidates
+}
+
+/// `bingo ln <path> <name>` from before several paths were accepted: a
+/// second argument is a name only after a file that exists, relative to
+/// the working directory. `bingo ln ls cat` adds two commands, `--name`
+/// names a single one.
+pub fn is_name(inputs: &[String]) -> bool {
+    inputs.len() == 2 && Path::new(&inputs[0]).is_file() && is_bare(&inputs[1])
+}
+
+fn free_name(config: &BingoConfig, name: &str) -> String {
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| config.get_executable(candidate).is_err())
+        .unwrap()
+}
+
+/// Add `path` as `name`, applying `policy` if the name is taken. Names in
+/// `earlier`, added by the same command, are never replaced.
+pub fn add_file(
+    config: &mut BingoConfig,
+    txn: &mut Transaction,
+    path: &Path,
+    name: &str,
+    executable_type: ExecutableType,
+    policy: OnConflict,
+    earlier: &[Added],
+) -> BingoResult<Added> {
+    let taken = config.get_executable(name).is_ok();
+    let added_now = earlier.iter().any(|a| a.name == name);
+    let (name, outcome) = match policy {
+        _ if !taken => (name.to_string(), Outcome::Added),
+        OnConflict::Fail => return Err(BingoError::DuplicateExecutableName(name.to_string())),
+        OnConflict::Skip => (name.to_string(), Outcome::Skipped),
+        OnConflict::Replace if added_now => (name.to_string(), Outcome::Skipped),
+        OnConflict::Replace => {
+            config.remove_executable(txn, name)?;
+            (name.to_string(), Outcome::Replaced)
+        }
+        OnConflict::Rename => (free_name(config, name), Outcome::Renamed(name.to_string())),
+    };
+    if outcome != Outcome::Skipped {
+        config.add_executable(txn, path, &name, executable_type)?;
+    }
+    Ok(Added {
+        path: path.to_path_buf(),
+        name,
+        outcome,
+    })
+}
@@ -1,34 +1,155 @@
+use super::add::{self, OnConflict, Outcome};
+use super::complete;
 use super::config;
+use super::doctor;
+use super::error::{BingoError, BingoResult};
+use super::list;
+use super::metadata;
+use super::naming::{self, NameRule};
+use super::paths::BingoPaths;
+use super::process;
+use super::search;
+use super::shell::{self, Shell};
+use super::update::{self, Freshness};
+use super::verify;
 use clap::{Arg, Command};
 use colored::*;
+use std::io::IsTerminal;
 
-fn msg(msg: &str) -> String {
-    format!("{} {}", msg, config::get_bingo_bin_dir().green())
+fn msg(msg: &str, bin_dir: &str) -> String {
+    format!("{} {}", msg, bin_dir.green())
 }
 
-fn build_parser() -> Command {
+fn no_exec_arg() -> Arg {
+    Arg::new("no-exec")
+        .long("no-exec")
+        .action(clap::ArgAction::SetTrue)
+        .help("Run as a child of bingo instead of replacing the bingo process")
+}
+
+fn version_arg() -> Arg {
+    Arg::new("version").long("version").value_name("VERSION")
+}
+
+fn description_arg() -> Arg {
+    Arg::new("description")
+        .long("description")
+        .value_name("TEXT")
+        .help("Free-text description shown by `bingo info`")
+}
+
+fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_parser(["plain", "table", "json"])
+        .default_value("plain")
+        .help("Output format")
+}
+
+fn long_arg() -> Arg {
+    Arg::new("long")
+        .short('l')
+        .action(clap::ArgAction::SetTrue)
+        .help("Show type, target status, size and install time")
+}
+
+fn tag_arg() -> Arg {
+    Arg::new("tag").long("tag").value_name("TAG")
+}
+
+/// Arguments shared by `cp` and `ln`.
+fn add_args() -> Vec<Arg> {
+    vec![
+        Arg::new("paths")
+            .required(true)
+            .num_args(1..)
+            .value_name("PATH")
+            .help("Executables, directories or glob patterns, a name may follow a single existing file"),
+        Arg::new("name")
+            .long("name")
+            .value_name("NAME")
+            .conflicts_with_all(["prefix", "suffix"])
+            .help("Add a single file as NAME"),
+        Arg::new("recursive")
+            .short('r')
+            .long("recursive")
+            .action(clap::ArgAction::SetTrue)
+            .help("Also add the executables in subdirectories"),
+        Arg::new("prefix")
+            .long("prefix")
+            .value_name("PREFIX")
+            .allow_hyphen_values(true)
+            .help("Put PREFIX before every derived name"),
+        Arg::new("suffix")
+            .long("suffix")
+            .value_name("SUFFIX")
+            .allow_hyphen_values(true)
+            .help("Put SUFFIX after every derived name"),
+        Arg::new("name-rule")
+            .long("name-rule")
+            .value_name("RULES")
+            .value_parser(NameRule::NAMES.to_vec())
+            .value_delimiter(',')
+            .help("How to derive names from file names, comma separated steps [default: the name-rule setting]"),
+        Arg::new("on-conflict")
+            .long("on-conflict")
+            .value_parser(OnConflict::NAMES.to_vec())
+            .default_value("fail")
+            .conflicts_with("version")
+            .help("What to do when a name is already registered"),
+        Arg::new("which")
+            .long("which")
+            .action(clap::ArgAction::SetTrue)
+            .help("Only list where command names are found in PATH"),
+        Arg::new("nth")
+            .long("nth")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Take the Nth match in PATH instead of the first"),
+        no_exec_arg(),
+        description_arg(),
+        version_arg().help("Install as this version and make it active"),
+    ]
+}
+
+fn build_parser(bin_dir: &str) -> Command {
     Command::new("bingo")
         .about("Bingo")
+        .arg(
+            Arg::new("home")
+                .long("home")
+                .global(true)
+                .value_name("DIR")
+                .help("Use DIR as the bingo home instead of $BINGO_HOME or $HOME/.bingo"),
+        )
         .subcommand(
             Command::new("cp")
-                .about(msg("Copy executable to"))
-                .arg(Arg::new("path").required(true).help("Path to executable"))
-                .arg(Arg::new("name").help("Name of executable")),
+                .about(msg("Copy executables to", bin_dir))
+                .args(add_args()),
         )
         .subcommand(
             Command::new("ln")
-                .about(msg("Link executable to"))
-                .arg(Arg::new("path").required(true).help("Path to executable"))
-                .arg(Arg::new("name").help("Name of executable")),
+                .about(msg("Link executables to", bin_dir))
+                .args(add_args()),
         )
         .subcommand(
             Command::new("rm")
-                .about(msg("Remove executable from "))
-                .arg(Arg::new("name").required(true).help("Name of executable")),
+                .about(msg("Remove executable from ", bin_dir))
+                .arg(
+                    Arg::new("name")
+                        .required_unless_present("tag")
+                        .help("Name of executable"),
+                )
+                .arg(version_arg().help("Only remove this version"))
+                .arg(
+                    tag_arg()
+                        .conflicts_with_all(["name", "version"])
+                        .help("Remove every executable with this tag"),
+                ),
         )
         .subcommand(
             Command::new("mv")
-                .about(msg("Rename executable in "))
+                .about(msg("Rename executable in ", bin_dir))
                 .arg(Arg::new("old").required(true).help("Name of executable"))
                 .arg(
                     Arg::new("new")
@@ -36,11 +157,59 @@ fn build_parser() -> Command {
                         .help("New name of executable"),
                 ),
         )
-        .subcommand(Command::new("ls").about(msg("List executables in")))
+        .subcommand(
+            Command::new("ls")
+                .about(msg("List executables in", bin_dir))
+                .arg(
+                    Arg::new("patterns")
+                        .num_args(0..)
+                        .help("Only names matching these globs, like 'py*'"),
+                )
+                .arg(format_arg())
+                .arg(long_arg())
+                .arg(
+                    Arg::new("type")
+                        .long("type")
+                        .value_parser(["copy", "link", "alias"])
+                        .help("Only executables of this type"),
+                )
+                .arg(
+                    Arg::new("broken")
+                        .long("broken")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Only executables whose target is gone"),
+                )
+                .arg(
+                    Arg::new("tag")
+                        .long("tag")
+                        .action(clap::ArgAction::Append)
+                        .help("Only executables with this tag, can be repeated"),
+                )
+                .arg(
+                    Arg::new("sort")
+                        .long("sort")
+                        .value_parser(["name", "added", "size"])
+                        .help("Sort by name, install time or size instead of registry order"),
+                ),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("Fuzzy search names, descriptions, tags and paths")
+                .arg(Arg::new("query").required(true))
+                .arg(format_arg())
+                .arg(long_arg()),
+        )
         .subcommand(
             Command::new("run")
                 .visible_alias("r")
-                .about(msg("Run executable in "))
+                .about(msg("Run executable in ", bin_dir))
+                .arg(version_arg().help("Run this version instead of the active one"))
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Run even if the executable is disabled"),
+                )
                 .arg(Arg::new("name").required(true).help("Name of executable"))
                 .arg(
                     Arg::new("args")
@@ -50,176 +219,1292 @@ fn build_parser() -> Command {
                         .help("Arguments"),
                 ),
         )
+        .subcommand(
+            Command::new("use")
+                .about("Switch the active version of an executable")
+                .arg(Arg::new("name").required(true).help("Name of executable"))
+                .arg(Arg::new("version").required(true).help("Version label")),
+        )
+        .subcommand(
+            Command::new("versions")
+                .about("List installed versions of an executable")
+                .arg(Arg::new("name").required(true).help("Name of executable")),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Show what was installed for an executable")
+                .arg(Arg::new("name").required(true).help("Name of executable")),
+        )
+        .subcommand(
+            Command::new("alias")
+                .about(msg("Add an alias with fixed arguments to", bin_dir))
+                .arg(Arg::new("name").required(true).help("Name of alias"))
+                .arg(
+                    Arg::new("target")
+                        .required(true)
+                        .help("Name of executable or absolute path"),
+                )
+                .arg(
+                    Arg::new("args")
+                        .action(clap::ArgAction::Append)
+                        .allow_hyphen_values(true)
+                        .help("Arguments put before the ones given when running"),
+                ),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Manage bingo.json")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("migrate")
+                        .about("Upgrade bingo.json to the current schema")
+                        .arg(
+                            Arg::new("dry-run")
+                                .long("dry-run")
+                                .action(clap::ArgAction::SetTrue)
+                                .help("Show the migration steps and result without saving"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("get").about("Show a setting").arg(
+                        Arg::new("key")
+                            .required(true)
+                            .value_parser(config::Settings::KEYS.to_vec()),
+                    ),
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("Change a setting")
+                        .arg(
+                            Arg::new("key")
+                                .required(true)
+                                .value_parser(config::Settings::KEYS.to_vec()),
+                        )
+                        .arg(Arg::new("value").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("env")
+                .about("Manage environment variables of an executable")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("set")
+                        .about("Set environment variables")
+                        .arg(Arg::new("name").required(true).help("Name of executable"))
+                        .arg(
+                            Arg::new("vars")
+                                .required(true)
+                                .action(clap::ArgAction::Append)
+                                .help("KEY=VALUE pairs"),
+                        )
+                        .arg(
+                            Arg::new("prepend")
+                                .long("prepend")
+                                .action(clap::ArgAction::SetTrue)
+                                .help("Prepend to the current value like PATH"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("unset")
+                        .about("Remove environment variables when running")
+                        .arg(Arg::new("name").required(true).help("Name of executable"))
+                        .arg(
+                            Arg::new("keys")
+                                .required(true)
+                                .action(clap::ArgAction::Append)
+                                .help("Variable names"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("rm")
+                        .about("Forget environment variable settings")
+                        .arg(Arg::new("name").required(true).help("Name of executable"))
+                        .arg(
+                            Arg::new("keys")
+                                .required(true)
+                                .action(clap::ArgAction::Append)
+                                .help("Variable names"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("ls")
+                        .about("List environment variable settings")
+                        .arg(Arg::new("name").required(true).help("Name of executable")),
+                ),
+        )
+        .subcommand(
+            Command::new("args")
+                .about("Show or set default arguments of an executable")
+                .arg(Arg::new("name").required(true).help("Name of executable"))
+                .arg(
+                    Arg::new("args")
+                        .action(clap::ArgAction::Append)
+                        .allow_hyphen_values(true)
+                        .help("Arguments placed before the ones given when running"),
+                )
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("args")
+                        .help("Remove default arguments"),
+                ),
+        )
+        .subcommand(
+            Command::new("cwd")
+                .about("Show or set the working directory of an executable")
+                .arg(Arg::new("name").required(true).help("Name of executable"))
+                .arg(Arg::new("dir").help("Working directory"))
+                .arg(
+                    Arg::new("clear")
+                        .long("clear")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("dir")
+                        .help("Run in the caller's working directory"),
+                ),
+        )
+        .subcommand(
+            Command::new("disable")
+                .about(msg("Take an executable out of", bin_dir))
+                .arg(
+                    Arg::new("names")
+                        .required(true)
+                        .num_args(1..)
+                        .help("Names of executables"),
+                ),
+        )
+        .subcommand(
+            Command::new("enable")
+                .about(msg("Put a disabled executable back into", bin_dir))
+                .arg(
+                    Arg::new("names")
+                        .required(true)
+                        .num_args(1..)
+                        .help("Names of executables"),
+                ),
+        )
+        .subcommand(
+            Command::new("tag")
+                .about("Group executables with tags")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("add")
+                        .about("Tag an executable")
+                        .arg(Arg::new("name").required(true).help("Name of executable"))
+                        .arg(Arg::new("tags").required(true).num_args(1..)),
+                )
+                .subcommand(
+                    Command::new("rm")
+                        .about("Remove tags from an executable")
+                        .arg(Arg::new("name").required(true).help("Name of executable"))
+                        .arg(Arg::new("tags").required(true).num_args(1..)),
+                )
+                .subcommand(
+                    Command::new("ls")
+                        .about("List all tags, or the tags of an executable")
+                        .arg(Arg::new("name").help("Name of executable")),
+                )
+                .subcommand(
+                    Command::new("disable")
+                        .about(msg("Take every executable with a tag out of", bin_dir))
+                        .arg(Arg::new("tag").required(true)),
+                )
+                .subcommand(
+                    Command::new("enable")
+                        .about(msg("Put every executable with a tag back into", bin_dir))
+                        .arg(Arg::new("tag").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("adopt")
+                .about(msg("Register files put by hand into", bin_dir))
+                .arg(
+                    Arg::new("files")
+                        .num_args(1..)
+                        .required_unless_present("all")
+                        .help("Names or paths of files in the bin dir"),
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("files")
+                        .help("Adopt every unregistered file"),
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Copy changed sources again")
+                .arg(
+                    Arg::new("names")
+                        .num_args(1..)
+                        .required_unless_present_any(["all", "tag"])
+                        .help("Names of executables"),
+                )
+                .arg(
+                    Arg::new("all")
+                        .long("all")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with_all(["names", "tag"])
+                        .help("Update every copied executable"),
+                )
+                .arg(
+                    tag_arg()
+                        .conflicts_with("names")
+                        .help("Update every executable with this tag"),
+                ),
+        )
+        .subcommand(Command::new("outdated").about("List copies whose source changed or vanished"))
+        .subcommand(
+            Command::new("verify")
+                .about("Check installed files against their recorded checksums")
+                .arg(
+                    Arg::new("names")
+                        .num_args(0..)
+                        .help("Names of executables, all of them by default"),
+                ),
+        )
+        .subcommand(
+            Command::new("init")
+                .about("Print the shell setup that puts the bin dir on PATH")
+                .arg(
+                    Arg::new("shell")
+                        .value_parser(Shell::NAMES.to_vec())
+                        .required_unless_present("check"),
+                )
+                .arg(
+                    Arg::new("completions")
+                        .long("completions")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Also set up completion, see `bingo completions`"),
+                )
+                .arg(
+                    Arg::new("command-not-found")
+                        .long("command-not-found")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Run registered executables that are not on PATH through bingo"),
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with_all(["shell", "completions", "command-not-found"])
+                        .help("Report whether and where the bin dir is on the current PATH"),
+                ),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print the completion script for a shell")
+                .arg(
+                    Arg::new("shell")
+                        .required(true)
+                        .value_parser(Shell::NAMES.to_vec()),
+                ),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about(msg("Check the registry against", bin_dir))
+                .arg(
+                    Arg::new("fix")
+                        .long("fix")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Relink, re-copy, adopt orphans and prune dead entries"),
+                ),
+        )
 }
 
-fn run_executable(c: &config::BingoConfigFile, name: &str, args: Vec<String>, alert: bool) {
-    let executable = &c.config.executables;
-    let executable = executable.iter().find(|e| e.name == name);
-    match executable {
-        Some(e) => {
-            let path = e.path.clone();
-            let path = std::path::Path::new(&path);
-            let status = std::process::Command::new(path).args(args).status();
-            match status {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            }
+/// Run a registered executable and return the code bingo should exit with.
+fn run_executable(
+    c: &config::BingoConfigFile,
+    name: &str,
+    version: Option<&str>,
+    force: bool,
+    args: Vec<String>,
+) -> i32 {
+    let mut chain = match c.config.resolve(name) {
+        Ok(chain) => chain,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
         }
-        None => {
-            if alert {
-                eprintln!("Executable {} not found.", name);
-                std::process::exit(1);
+    };
+    if !force {
+        if let Some(e) = chain.iter().find(|e| e.disabled) {
+            eprintln!("{}", BingoError::ExecutableDisabled(e.name.clone()));
+            return 1;
+        }
+    }
+    let versioned;
+    if let Some(label) = version {
+        let target = chain.pop().unwrap();
+        versioned = match target.get_version(label) {
+            Ok(v) => config::Executable {
+                path: v.path.clone(),
+                executable_type: v.executable_type,
+                ..target.clone()
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1;
+            }
+        };
+        chain.push(&versioned);
+    }
+    let verified;
+    if c.config.settings.strict {
+        let target = chain[chain.len() - 1];
+        let registered = c.config.get_executable(&target.name).unwrap();
+        match verify::verify_before_run(registered, version, &c.paths) {
+            // the source of a copy may have changed since it was checked
+            Ok(Some(file)) => {
+                verified = config::Executable {
+                    path: file,
+                    ..target.clone()
+                };
+                chain.pop();
+                chain.push(&verified);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("refusing to run in strict mode, see `bingo verify`");
+                return 1;
             }
         }
     }
+    let mut command = config::build_command(&chain, args);
+    if chain[chain.len() - 1].exec {
+        let err = process::exec(&mut command);
+        eprintln!("{}", err);
+        return 1;
+    }
+    match process::spawn_and_wait(&mut command) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
 }
 
-static SUBCOMMANDS: &[&str] = &["cp", "ln", "rm", "mv", "ls", "run", "r"];
+/// The name of `file` in the bin dir. A bare name is taken as is, a path
+/// has to point into the bin dir.
+fn bin_dir_name(paths: &BingoPaths, file: &str) -> BingoResult<String> {
+    if !file.contains('/') {
+        return Ok(file.to_string());
+    }
+    let path = std::path::Path::new(file);
+    let parent = path.parent().and_then(|p| std::fs::canonicalize(p).ok());
+    let bin_dir = std::fs::canonicalize(&paths.bin_dir).ok();
+    match (parent, path.file_name()) {
+        (Some(parent), Some(name)) if bin_dir.as_ref() == Some(&parent) => {
+            Ok(name.to_string_lossy().to_string())
+        }
+        _ => Err(BingoError::NotInBinDir(file.to_string())),
+    }
+}
 
-pub fn cli_run() {
-    match config::BingoConfigFile::init() {
-        Ok(_) => {}
+fn exit_on_error<T>(result: BingoResult<T>) -> T {
+    match result {
+        Ok(t) => t,
         Err(e) => {
             eprintln!("{}", e);
             std::process::exit(1);
         }
     }
-    let mut config_file = config::BingoConfigFile::new();
-    match config_file.load() {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
+}
+
+static SUBCOMMANDS: &[&str] = &[
+    "cp",
+    "ln",
+    "rm",
+    "mv",
+    "ls",
+    "search",
+    "run",
+    "r",
+    "use",
+    "versions",
+    "info",
+    "alias",
+    "config",
+    "env",
+    "args",
+    "cwd",
+    "disable",
+    "enable",
+    "tag",
+    "adopt",
+    "update",
+    "outdated",
+    "verify",
+    "init",
+    "completions",
+    "__complete",
+    "doctor",
+];
+
+/// Subcommands that may save the config.
+static MUTATING_SUBCOMMANDS: &[&str] = &[
+    "cp", "ln", "rm", "mv", "use", "alias", "config", "env", "args", "cwd", "disable", "enable",
+    "tag", "adopt", "update", "outdated", "doctor",
+];
+
+/// Remove a leading `--home DIR` or `--home=DIR` from `args`. It has to be
+/// known before looking up `bingo <name>`, which bypasses the parser.
+fn take_home_arg(args: &mut Vec<String>) -> Option<String> {
+    if args.len() > 2 && args[1] == "--home" {
+        let home = args.remove(2);
+        args.remove(1);
+        return Some(home);
+    }
+    if args.len() > 1 {
+        if let Some(home) = args[1].strip_prefix("--home=") {
+            let home = home.to_string();
+            args.remove(1);
+            return Some(home);
+        }
+    }
+    None
+}
+
+fn open_config_file(home: Option<&str>) -> config::BingoConfigFile {
+    let paths = exit_on_error(BingoPaths::resolve(home));
+    let config_file = config::BingoConfigFile::new(paths);
+    exit_on_error(config_file.init());
+    config_file
+}
+
+/// `bingo cp` and `bingo ln`, all files are added or none.
+fn add_command(
+    config_file: &mut config::BingoConfigFile,
+    args: &clap::ArgMatches,
+    executable_type: config::ExecutableType,
+) {
+    let mut inputs = args
+        .get_many::<String>("paths")
+        .unwrap()
+        .cloned()
+        .collect::<Vec<_>>();
+    let name = match args.get_one::<String>("name") {
+        Some(name) => Some(name.clone()),
+        None if add::is_name(&inputs) => inputs.pop(),
+        None => None,
+    };
+    let which = args.get_flag("which");
+    let nth = args.get_one::<u64>("nth").map_or(1, |n| *n as usize);
+    let path_var = std::env::var("PATH").unwrap_or_default();
+    for input in inputs.iter_mut().filter(|input| add::is_bare(input)) {
+        let candidates = add::path_candidates(input, &path_var, &config_file.paths.bin_dir);
+        if candidates.is_empty() {
+            // otherwise reported as missing when adding
+            if which {
+                println!("{} is not in PATH", input);
+            }
+            continue;
+        }
+        println!("{} in PATH:", input);
+        for (i, candidate) in candidates.iter().enumerate() {
+            let mark = if i + 1 == nth { "*" } else { " " };
+            println!("{} {}", mark, candidate.display());
+        }
+        if which {
+            continue;
+        }
+        match candidates.get(nth - 1) {
+            Some(candidate) => *input = candidate.to_str().unwrap().to_string(),
+            None => exit_on_error(Err(BingoError::NoSuchPathMatch {
+                name: input.clone(),
+                nth,
+                count: candidates.len(),
+            })),
         }
     }
+    if which {
+        return;
+    }
+    let files = exit_on_error(add::collect(&inputs, args.get_flag("recursive")));
+    if name.is_some() && files.len() > 1 {
+        exit_on_error::<()>(Err(BingoError::NameNeedsSingleFile(files.len())));
+    }
+    let policy = OnConflict::from(args.get_one::<String>("on-conflict").unwrap().as_str());
+    let prefix = args.get_one::<String>("prefix").map_or("", |s| s.as_str());
+    let suffix = args.get_one::<String>("suffix").map_or("", |s| s.as_str());
+    let rules = match args.get_many::<String>("name-rule") {
+        Some(rules) => rules.map(|rule| NameRule::from(rule.as_str())).collect(),
+        None => config_file.config.settings.name_rules(),
+    };
+    let result = config_file.transaction(|config, txn| {
+        let mut report: Vec<add::Added> = vec![];
+        for file in &files {
+            let name = match &name {
+                Some(name) => name.clone(),
+                None => {
+                    let file_name = file.file_name().and_then(|name| name.to_str());
+                    let file_name = file_name
+                        .ok_or_else(|| BingoError::NonUtf8FileName(file.display().to_string()))?;
+                    let name = naming::derive_name(file_name, &rules);
+                    format!("{}{}{}", prefix, name, suffix)
+                }
+            };
+            let added = match args.get_one::<String>("version") {
+                Some(label) => {
+                    config.add_version(txn, file, &name, label, executable_type)?;
+                    add::Added {
+                        path: file.clone(),
+                        name,
+                        outcome: Outcome::Added,
+                    }
+                }
+                None => add::add_file(config, txn, file, &name, executable_type, policy, &report)?,
+            };
+            if added.outcome != Outcome::Skipped {
+                let e = config.get_executable_mut(&added.name)?;
+                if args.get_flag("no-exec") {
+                    e.exec = false;
+                }
+                if let Some(description) = args.get_one::<String>("description") {
+                    e.metadata.description = Some(description.clone());
+                }
+            }
+            report.push(added);
+        }
+        Ok(report)
+    });
+    let report = exit_on_error(result);
+    // a single file added as asked needs no summary
+    if report.len() == 1 && report[0].outcome == Outcome::Added {
+        return;
+    }
+    let mut counts = [0; 4];
+    for added in &report {
+        let (index, status, note) = match &added.outcome {
+            Outcome::Added => (0, "added".green(), String::new()),
+            Outcome::Replaced => (1, "replaced".yellow(), String::new()),
+            Outcome::Renamed(wanted) => (2, "renamed".yellow(), format!(" ({} is taken)", wanted)),
+            Outcome::Skipped => (3, "skipped".red(), " (name is taken)".to_string()),
+        };
+        counts[index] += 1;
+        println!(
+            "{:<8} {} <- {}{}",
+            status,
+            added.name,
+            added.path.display(),
+            note
+        );
+    }
+    println!(
+        "{} added, {} replaced, {} renamed, {} skipped",
+        counts[0], counts[1], counts[2], counts[3]
+    );
+}
+
+pub fn cli_run() {
+    // exit quietly when the output is piped into e.g. `head`
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+    // colored already honours NO_COLOR and CLICOLOR_FORCE
+    if !std::io::stdout().is_terminal() && std::env::var_os("CLICOLOR_FORCE").is_none() {
+        colored::control::set_override(false);
+    }
+    let mut args = std::env::args().collect::<Vec<String>>();
+    let home = take_home_arg(&mut args);
+    // created once it is clear which home is meant, `--home` may follow
+    // the subcommand
+    let paths = exit_on_error(BingoPaths::resolve(home.as_deref()));
+    let mut config_file = config::BingoConfigFile::new(paths);
+    // asked by the completion scripts, which pass the words after `--`
+    if args.len() > 1 && args[1] == "__complete" {
+        config_file.quiet = true;
+        let _ = config_file.load();
+        let words = match args.iter().position(|a| a == "--") {
+            Some(i) => &args[i + 1..],
+            None => &args[2..],
+        };
+        let parser = build_parser(&config_file.paths.bin_dir);
+        match complete::complete(&parser, &config_file.config, words) {
+            complete::Completion::Words(words) => {
+                for word in words {
+                    println!("{}", word);
+                }
+            }
+            complete::Completion::Delegate(index) => println!(":delegate {}", index),
+        }
+        return;
+    }
     {
-        let args = std::env::args().collect::<Vec<String>>();
         if args.len() > 1 {
             let command = &args[1];
             let mut command_args = vec![];
             if !SUBCOMMANDS.contains(&command.as_str()) {
+                exit_on_error(config_file.init());
+                exit_on_error(config_file.load());
+                config_file.save_migrated(false);
                 // check if command is a executable
                 if args.len() > 2 {
                     command_args = args[2..].to_vec();
                 }
                 let executables = &config_file.config.executables;
                 let executable = executables.iter().find(|e| e.name == *command);
-                if let Some(_) = executable {
-                    run_executable(&config_file, command, command_args, false);
-                    std::process::exit(0);
+                if executable.is_some() {
+                    let code = run_executable(&config_file, command, None, false, command_args);
+                    std::process::exit(code);
                 }
             }
         }
     }
 
-    let default_name = "".to_string();
-    let matches = build_parser().get_matches();
+    let matches = build_parser(&config_file.paths.bin_dir).get_matches_from(&args);
+    let home = matches.get_one::<String>("home").or(home.as_ref());
+    config_file = open_config_file(home.map(|home| home.as_str()));
+    // hold the lock from loading to saving
+    let _lock = match matches.subcommand_name() {
+        Some(name) if MUTATING_SUBCOMMANDS.contains(&name) => {
+            Some(exit_on_error(config_file.lock()))
+        }
+        _ => None,
+    };
+    exit_on_error(config_file.load());
+    // `config migrate` reports and saves the migration itself
+    if matches.subcommand_name() != Some("config") {
+        config_file.save_migrated(_lock.is_some());
+    }
     match matches.subcommand() {
         Some(("ln", args)) => {
-            let path = args.get_one::<String>("path").unwrap().clone();
-            let path = std::path::Path::new(&path);
-            let mut name = args
-                .get_one::<String>("name")
-                .unwrap_or(&default_name)
-                .clone();
-            if name.len() == 0 {
-                name = path.file_name().unwrap().to_str().unwrap().to_string();
-                name = name.split('.').collect::<Vec<&str>>()[0].to_string();
-            }
-            match config_file.config.add_executable(
-                &path,
-                &name,
-                config::ExecutableType::LinkBinary,
-            ) {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            }
-            config_file.save().unwrap();
+            add_command(&mut config_file, args, config::ExecutableType::LinkBinary);
         }
         Some(("cp", args)) => {
-            let path = args.get_one::<String>("path").unwrap().clone();
-            let path = std::path::Path::new(&path);
-            let mut name = args
-                .get_one::<String>("name")
-                .unwrap_or(&default_name)
-                .clone();
-            if name.len() == 0 {
-                name = path.file_name().unwrap().to_str().unwrap().to_string();
-                name = name.split('.').collect::<Vec<&str>>()[0].to_string();
-            }
-            match config_file
-                .config
-                .add_executable(&path, &name, config::ExecutableType::Binary)
-            {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            }
-            config_file.save().unwrap();
+            add_command(&mut config_file, args, config::ExecutableType::Binary);
         }
         Some(("rm", args)) => {
+            if let Some(tag) = args.get_one::<String>("tag") {
+                let names = exit_on_error(config_file.config.tagged(tag));
+                exit_on_error(config_file.transaction(|config, txn| {
+                    for name in &names {
+                        config.remove_executable(txn, name)?;
+                    }
+                    Ok(())
+                }));
+                return;
+            }
             let name = args.get_one::<String>("name").unwrap().clone();
-            match config_file.config.remove_executable(&name) {
+            let result =
+                config_file.transaction(|config, txn| match args.get_one::<String>("version") {
+                    Some(label) => config.remove_version(txn, &name, label),
+                    None => config.remove_executable(txn, &name),
+                });
+            match result {
                 Ok(_) => {}
                 Err(e) => {
                     eprintln!("{}", e);
                     std::process::exit(1);
                 }
             }
-            config_file.save().unwrap();
         }
         Some(("mv", args)) => {
             let old = args.get_one::<String>("old").unwrap().clone();
             let new = args.get_one::<String>("new").unwrap().clone();
-            match config_file.config.rename_executable(&old, &new) {
+            let result =
+                config_file.transaction(|config, txn| config.rename_executable(txn, &old, &new));
+            match result {
                 Ok(_) => {}
                 Err(e) => {
                     eprintln!("{}", e);
                     std::process::exit(1);
                 }
             }
-            config_file.save().unwrap();
         }
 
-        Some(("ls", _)) => {
-            let executables = config_file.config.executables;
-            if executables.len() == 0 {
-                println!("No executables found.");
-                std::process::exit(0);
-            }
-            for (mut index, e) in executables.iter().enumerate() {
-                index += 1;
-                match e.executable_type {
-                    config::ExecutableType::Binary => {
-                        println!("{index}: {} => {}", e.name, e.path.green());
-                    }
-                    config::ExecutableType::LinkBinary => {
-                        println!("{index}: {} -> {}", e.name, e.path.cyan());
-                    }
-                }
+        Some(("ls", args)) => {
+            let c = &config_file.config;
+            let patterns = args.get_many::<String>("patterns").into_iter().flatten();
+            let patterns = patterns.map(|p| match glob::Pattern::new(p) {
+                Ok(pattern) => Ok(pattern),
+                Err(e) => Err(BingoError::InvalidPattern(format!("{}: {}", p, e))),
+            });
+            let filter = list::Filter {
+                kind: args.get_one::<String>("type").cloned(),
+                broken: args.get_flag("broken"),
+                tags: args
+                    .get_many::<String>("tag")
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect(),
+                patterns: exit_on_error(patterns.collect()),
+            };
+            let mut executables = c
+                .executables
+                .iter()
+                .filter(|e| filter.matches(c, &config_file.paths, e))
+                .collect::<Vec<_>>();
+            if let Some(key) = args.get_one::<String>("sort") {
+                list::sort(&mut executables, key.as_str().into());
             }
+            let format = list::Format::from(args.get_one::<String>("format").unwrap().as_str());
+            list::print(
+                c,
+                &config_file.paths,
+                &executables,
+                format,
+                args.get_flag("long"),
+            );
+        }
+
+        Some(("search", args)) => {
+            let c = &config_file.config;
+            let query = args.get_one::<String>("query").unwrap();
+            let executables = search::search(query, &c.executables);
+            let format = list::Format::from(args.get_one::<String>("format").unwrap().as_str());
+            list::print(
+                c,
+                &config_file.paths,
+                &executables,
+                format,
+                args.get_flag("long"),
+            );
         }
 
         Some(("run", args)) => {
             let name = args.get_one::<String>("name").unwrap().clone();
+            let version = args.get_one::<String>("version").map(|v| v.as_str());
+            let force = args.get_flag("force");
             let args = args.get_many::<String>("args");
 
             let args = match args {
-                Some(a) => a.map(|a| a.clone()).collect::<Vec<String>>(),
+                Some(a) => a.cloned().collect::<Vec<String>>(),
                 None => vec![],
             };
-            run_executable(&config_file, &name, args, true);
+            let code = run_executable(&config_file, &name, version, force, args);
+            std::process::exit(code);
         }
+        Some(("use", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let label = args.get_one::<String>("version").unwrap();
+            exit_on_error(
+                config_file
+                    .transaction(|config, txn| config.use_version(txn, name, label).map(|_| ())),
+            );
+        }
+
+        Some(("versions", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let e = exit_on_error(config_file.config.get_executable(name));
+            if e.versions.is_empty() {
+                println!("{} has no versions.", name);
+                return;
+            }
+            for v in &e.versions {
+                if e.version.as_ref() == Some(&v.label) {
+                    println!("* {} => {}", v.label.green(), v.path);
+                } else {
+                    println!("  {} => {}", v.label, v.path);
+                }
+            }
+        }
+
+        Some(("info", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let e = exit_on_error(config_file.config.get_executable(name));
+            let m = &e.metadata;
+            let unknown = || "unknown".to_string();
+            println!("{:<14}{}", "name:", e.name.green());
+            println!("{:<14}{}", "type:", e.executable_type.kind());
+            println!("{:<14}{}", "source:", e.path);
+            if let Some(label) = &e.version {
+                println!("{:<14}{}", "version:", label);
+            }
+            if e.disabled {
+                println!("{:<14}{}", "status:", "disabled".yellow());
+            }
+            if let Some(description) = &m.description {
+                println!("{:<14}{}", "description:", description);
+            }
+            if !matches!(e.executable_type, config::ExecutableType::Alias) {
+                println!(
+                    "{:<14}{}",
+                    "sha256:",
+                    m.sha256.clone().unwrap_or_else(unknown)
+                );
+                let size = m.size.map(|s| format!("{} bytes", s));
+                println!("{:<14}{}", "size:", size.unwrap_or_else(unknown));
+                let mtime = m.source_mtime.map(metadata::format_time);
+                println!("{:<14}{}", "source mtime:", mtime.unwrap_or_else(unknown));
+            }
+            let installed_at = m.installed_at.map(metadata::format_time);
+            println!(
+                "{:<14}{}",
+                "installed at:",
+                installed_at.unwrap_or_else(unknown)
+            );
+            let installed_by = m.installed_by.as_ref().map(|v| format!("bingo {}", v));
+            println!(
+                "{:<14}{}",
+                "installed by:",
+                installed_by.unwrap_or_else(unknown)
+            );
+        }
+
+        Some(("alias", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let target = args.get_one::<String>("target").unwrap();
+            let alias_args = match args.get_many::<String>("args") {
+                Some(a) => a.cloned().collect::<Vec<String>>(),
+                None => vec![],
+            };
+            exit_on_error(config_file.transaction(|config, txn| {
+                config.add_alias(txn, name, target, alias_args).map(|_| ())
+            }));
+        }
+
+        Some(("config", args)) => match args.subcommand() {
+            Some(("migrate", args)) => {
+                let path = config_file.paths.config_file.clone();
+                let json = match std::fs::read_to_string(&path) {
+                    Ok(json) => json,
+                    Err(e) => exit_on_error(Err(BingoError::ConfigFileNotFound(e.to_string()))),
+                };
+                let migrated = match exit_on_error(config::migrate_json(&json)) {
+                    Some(migrated) => migrated,
+                    None => {
+                        println!(
+                            "{} is up to date (schema {}).",
+                            path,
+                            config::CONFIG_SCHEMA_VERSION
+                        );
+                        return;
+                    }
+                };
+                for step in &migrated.steps {
+                    println!(
+                        "schema {} -> {}: {}",
+                        step.from,
+                        step.from + 1,
+                        step.description
+                    );
+                }
+                if args.get_flag("dry-run") {
+                    println!("{}", migrated.json);
+                    return;
+                }
+                // `load` has already migrated the config in memory
+                exit_on_error(config_file.save());
+                println!(
+                    "Migrated {}, the original is kept as {}",
+                    path,
+                    config_file.migration_backup_path(migrated.from)
+                );
+            }
+            Some(("get", args)) => {
+                let key = args.get_one::<String>("key").unwrap();
+                println!("{}", exit_on_error(config_file.config.settings.get(key)));
+            }
+            Some(("set", args)) => {
+                let key = args.get_one::<String>("key").unwrap();
+                let value = args.get_one::<String>("value").unwrap();
+                exit_on_error(config_file.config.settings.set(key, value));
+                exit_on_error(config_file.save());
+            }
+            _ => unreachable!(),
+        },
+
+        Some(("env", args)) => match args.subcommand() {
+            Some(("set", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                let prepend = args.get_flag("prepend");
+                let e = exit_on_error(config_file.config.get_executable_mut(name));
+                for var in args.get_many::<String>("vars").unwrap() {
+                    let (key, value) = exit_on_error(config::parse_env_var(var));
+                    let value = if prepend {
+                        config::EnvValue::Prepend(value)
+                    } else {
+                        config::EnvValue::Set(value)
+                    };
+                    e.env.insert(key, value);
+                }
+                exit_on_error(config_file.save());
+            }
+            Some(("unset", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                let e = exit_on_error(config_file.config.get_executable_mut(name));
+                for key in args.get_many::<String>("keys").unwrap() {
+                    e.env.insert(key.clone(), config::EnvValue::Unset);
+                }
+                exit_on_error(config_file.save());
+            }
+            Some(("rm", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                let e = exit_on_error(config_file.config.get_executable_mut(name));
+                for key in args.get_many::<String>("keys").unwrap() {
+                    e.env.remove(key);
+                }
+                exit_on_error(config_file.save());
+            }
+            Some(("ls", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                let e = exit_on_error(config_file.config.get_executable(name));
+                for (key, value) in &e.env {
+                    match value {
+                        config::EnvValue::Set(v) => println!("{}={}", key, v),
+                        config::EnvValue::Unset => println!("{} {}", key, "(unset)".yellow()),
+                        config::EnvValue::Prepend(v) => println!("{}={}:${}", key, v, key),
+                    }
+                }
+            }
+            _ => unreachable!(),
+        },
+
+        Some(("args", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let e = exit_on_error(config_file.config.get_executable_mut(name));
+            match args.get_many::<String>("args") {
+                Some(a) => e.args = a.cloned().collect(),
+                None if args.get_flag("clear") => e.args.clear(),
+                None => {
+                    println!("{}", e.args.join(" "));
+                    return;
+                }
+            }
+            exit_on_error(config_file.save());
+        }
+
+        Some(("cwd", args)) => {
+            let name = args.get_one::<String>("name").unwrap();
+            let e = exit_on_error(config_file.config.get_executable_mut(name));
+            match args.get_one::<String>("dir") {
+                Some(dir) => {
+                    let dir = match std::fs::canonicalize(dir) {
+                        Ok(dir) if dir.is_dir() => dir,
+                        _ => exit_on_error(Err(BingoError::FileNotFound(dir.clone()))),
+                    };
+                    e.cwd = Some(dir.to_str().unwrap().to_string());
+                }
+                None if args.get_flag("clear") => e.cwd = None,
+                None => {
+                    if let Some(cwd) = &e.cwd {
+                        println!("{}", cwd);
+                    }
+                    return;
+                }
+            }
+            exit_on_error(config_file.save());
+        }
+
+        Some((action @ ("disable" | "enable"), args)) => {
+            let names = args.get_many::<String>("names").unwrap();
+            exit_on_error(config_file.transaction(|config, txn| {
+                for name in names {
+                    match action {
+                        "disable" => config.disable_executable(txn, name)?,
+                        _ => config.enable_executable(txn, name)?,
+                    }
+                }
+                Ok(())
+            }));
+        }
+
+        Some(("tag", args)) => match args.subcommand() {
+            Some(("add", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                for tag in args.get_many::<String>("tags").unwrap() {
+                    exit_on_error(config_file.config.add_tag(name, tag));
+                }
+                exit_on_error(config_file.save());
+            }
+            Some(("rm", args)) => {
+                let name = args.get_one::<String>("name").unwrap();
+                for tag in args.get_many::<String>("tags").unwrap() {
+                    exit_on_error(config_file.config.remove_tag(name, tag));
+                }
+                exit_on_error(config_file.save());
+            }
+            Some(("ls", args)) => match args.get_one::<String>("name") {
+                Some(name) => {
+                    let e = exit_on_error(config_file.config.get_executable(name));
+                    for tag in &e.tags {
+                        println!("{}", tag);
+                    }
+                }
+                None => {
+                    let mut counts = std::collections::BTreeMap::new();
+                    for e in &config_file.config.executables {
+                        for tag in &e.tags {
+                            *counts.entry(tag).or_insert(0) += 1;
+                        }
+                    }
+                    for (tag, count) in counts {
+                        println!("{} ({})", tag, count);
+                    }
+                }
+            },
+            Some((action, args)) => {
+                let tag = args.get_one::<String>("tag").unwrap();
+                let names = exit_on_error(config_file.config.tagged(tag));
+                exit_on_error(config_file.transaction(|config, txn| {
+                    for name in &names {
+                        match action {
+                            "disable" => config.disable_executable(txn, name)?,
+                            _ => config.enable_executable(txn, name)?,
+                        }
+                    }
+                    Ok(())
+                }));
+                println!("{}d {}", action, names.join(", "));
+            }
+            _ => unreachable!(),
+        },
+
+        Some(("adopt", args)) => {
+            let names = if args.get_flag("all") {
+                config_file.config.unregistered_files(&config_file.paths)
+            } else {
+                let files = args.get_many::<String>("files").unwrap();
+                let names = files.map(|file| bin_dir_name(&config_file.paths, file));
+                exit_on_error(names.collect::<BingoResult<Vec<String>>>())
+            };
+            if names.is_empty() {
+                println!("Nothing to adopt.");
+                return;
+            }
+            let adopted = exit_on_error(config_file.transaction(|config, txn| {
+                let mut adopted = vec![];
+                for name in &names {
+                    let e = config.adopt_executable(&txn.paths, name)?;
+                    adopted.push((e.name.clone(), e.path.clone(), e.executable_type));
+                }
+                Ok(adopted)
+            }));
+            for (name, path, executable_type) in adopted {
+                match executable_type {
+                    config::ExecutableType::LinkBinary => println!("{} -> {}", name, path.cyan()),
+                    _ => println!("{} => {}", name, path.green()),
+                }
+            }
+        }
+
+        Some(("update", args)) => {
+            let c = &config_file.config;
+            let names = match args.get_one::<String>("tag") {
+                Some(tag) => Some(exit_on_error(c.tagged(tag))),
+                None => args
+                    .get_many::<String>("names")
+                    .map(|names| names.cloned().collect()),
+            };
+            let executables = match names {
+                Some(names) => exit_on_error(
+                    names
+                        .iter()
+                        .map(|name| c.get_executable(name))
+                        .collect::<BingoResult<Vec<_>>>(),
+                ),
+                None => c.executables.iter().collect(),
+            };
+            let checked = executables
+                .into_iter()
+                .flat_map(|e| update::copies(e, &config_file.paths))
+                .map(|copy| {
+                    let freshness = copy.freshness();
+                    let label = copy.label.map(|l| l.to_string());
+                    (copy.name, copy.executable.to_string(), label, freshness)
+                })
+                .collect::<Vec<_>>();
+            exit_on_error(config_file.transaction(|config, txn| {
+                for (_, name, label, freshness) in &checked {
+                    match freshness {
+                        Freshness::Changed => config.refresh_copy(txn, name, label.as_deref())?,
+                        Freshness::Touched => config.touch_copy(name, label.as_deref())?,
+                        _ => {}
+                    }
+                }
+                Ok(())
+            }));
+            let mut gone = 0;
+            for (name, _, _, freshness) in &checked {
+                match freshness {
+                    Freshness::UpToDate | Freshness::Touched => {
+                        println!("{}: {}", "up to date".green(), name)
+                    }
+                    Freshness::Changed => println!("{}: {}", "updated".cyan(), name),
+                    Freshness::SourceGone => {
+                        gone += 1;
+                        println!("{}: {}", "source gone".red(), name);
+                    }
+                }
+            }
+            if gone > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Some(("outdated", _)) => {
+            let mut outdated = 0;
+            let mut touched = vec![];
+            for e in &config_file.config.executables {
+                for copy in update::copies(e, &config_file.paths) {
+                    match copy.freshness() {
+                        Freshness::UpToDate => continue,
+                        Freshness::Touched => {
+                            touched.push((e.name.clone(), copy.label.map(|l| l.to_string())));
+                            continue;
+                        }
+                        Freshness::Changed => println!("{}: {}", "changed".yellow(), copy.name),
+                        Freshness::SourceGone => {
+                            println!("{}: {} ({})", "source gone".red(), copy.name, copy.source)
+                        }
+                    }
+                    outdated += 1;
+                }
+            }
+            if !touched.is_empty() {
+                // only spares hashing them next time, failing is harmless
+                let _ = config_file.transaction(|config, _| {
+                    for (name, label) in &touched {
+                        config.touch_copy(name, label.as_deref())?;
+                    }
+                    Ok(())
+                });
+            }
+            if outdated == 0 {
+                println!("Everything is up to date.");
+                return;
+            }
+            std::process::exit(1);
+        }
+
+        Some(("verify", args)) => {
+            let executables = match args.get_many::<String>("names") {
+                Some(names) => exit_on_error(
+                    names
+                        .map(|name| config_file.config.get_executable(name))
+                        .collect::<BingoResult<Vec<_>>>(),
+                ),
+                None => config_file.config.executables.iter().collect(),
+            };
+            let mut failed = 0;
+            for e in executables {
+                for file in verify::installed_files(e, &config_file.paths) {
+                    match file.verify() {
+                        Ok(verify::Verified::Ok) => println!("{}: {}", "ok".green(), file.name),
+                        Ok(verify::Verified::NoChecksum) => {
+                            println!("{}: {}", "no checksum".yellow(), file.name)
+                        }
+                        Err(BingoError::ChecksumMismatch {
+                            name,
+                            expected,
+                            actual,
+                        }) => {
+                            failed += 1;
+                            println!("{}: {}", "mismatch".red(), name);
+                            println!("  expected {}", expected);
+                            println!("  actual   {}", actual);
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            println!("{}: {} ({})", "unreadable".red(), file.name, e);
+                        }
+                    }
+                }
+            }
+            if failed > 0 {
+                std::process::exit(1);
+            }
+        }
+
+        Some(("init", args)) => {
+            let bin_dir = &config_file.paths.bin_dir;
+            if args.get_flag("check") {
+                let path = std::env::var("PATH").unwrap_or_default();
+                let names = config_file
+                    .config
+                    .executables
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect::<Vec<_>>();
+                let check = shell::check_path(&path, bin_dir, &names);
+                let position = match check.position {
+                    Some(position) => position,
+                    None => {
+                        println!("{} is not on PATH, add `bingo init <shell>` to your shell's startup file.", bin_dir.red());
+                        std::process::exit(1);
+                    }
+                };
+                println!(
+                    "{} is entry {} of {} on PATH.",
+                    bin_dir.green(),
+                    position,
+                    check.entries
+                );
+                for (name, dir) in &check.shadowed {
+                    println!(
+                        "{} {} is shadowed by {}/{}",
+                        "warning:".yellow(),
+                        name,
+                        dir,
+                        name
+                    );
+                }
+                return;
+            }
+            let shell = Shell::from(args.get_one::<String>("shell").unwrap().as_str());
+            let options = shell::InitOptions {
+                completions: args.get_flag("completions"),
+                command_not_found: args.get_flag("command-not-found"),
+            };
+            let bingo = match config::bingo_command(&config_file.paths) {
+                Ok(bingo) => bingo,
+                Err(e) => exit_on_error(Err(BingoError::FileSystemError(e.to_string()))),
+            };
+            print!(
+                "{}",
+                shell::init_script(shell, &config_file.paths, &options, &bingo)
+            );
+        }
+
+        Some(("completions", args)) => {
+            let shell = Shell::from(args.get_one::<String>("shell").unwrap().as_str());
+            let bingo = match config::bingo_command(&config_file.paths) {
+                Ok(bingo) => bingo,
+                Err(e) => exit_on_error(Err(BingoError::FileSystemError(e.to_string()))),
+            };
+            print!("{}", shell::completion_script(shell, &bingo));
+        }
+
+        Some(("doctor", args)) => {
+            let problems = doctor::diagnose(&config_file.config, &config_file.paths);
+            if problems.is_empty() {
+                println!("No problems found.");
+                return;
+            }
+            if !args.get_flag("fix") {
+                for problem in &problems {
+                    println!("{}: {}", problem.class().yellow(), problem);
+                }
+                println!(
+                    "{} problem(s) found, run `bingo doctor --fix` to repair them.",
+                    problems.len()
+                );
+                std::process::exit(1);
+            }
+            for problem in &problems {
+                // one transaction per fix, a failing one leaves the others
+                match config_file.transaction(|config, txn| doctor::fix(config, txn, problem)) {
+                    Ok(done) => println!("{}: {}", problem.class().green(), done),
+                    Err(e) => println!("{}: {} ({})", problem.class().red(), problem, e),
+                }
+            }
+            // fixes can uncover new problems, e.g. pruning an alias target
+            let remaining = doctor::diagnose(&config_file.config, &config_file.paths);
+            if !remaining.is_empty() {
+                for problem in &remaining {
+                    println!("{}: {}", problem.class().red(), problem);
+                }
+                std::process::exit(1);
+            }
+        }
+
         _ => {
-            let _ = build_parser().print_help();
+            let _ = build_parser(&config_file.paths.bin_dir).print_help();
             std::process::exit(1);
         }
     }
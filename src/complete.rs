@@ -0,0 +1,189 @@
+use crate::config::BingoConfig;
+use clap::{Arg, Command};
+
+/// What the shell should offer for the word under the cursor.
+pub enum Completion {
+    Words(Vec<String>),
+    /// Complete the words from this index on as a command line of its own,
+    /// they are the arguments of a registered executable.
+    Delegate(usize),
+}
+
+fn executable_names(config: &BingoConfig) -> Vec<String> {
+    config.executables.iter().map(|e| e.name.clone()).collect()
+}
+
+fn tags(config: &BingoConfig) -> Vec<String> {
+    let mut tags = config
+        .executables
+        .iter()
+        .flat_map(|e| e.tags.iter().cloned())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn versions(config: &BingoConfig, name: Option<&str>) -> Vec<String> {
+    match name.and_then(|name| config.get_executable(name).ok()) {
+        Some(e) => e.versions.iter().map(|v| v.label.clone()).collect(),
+        None => vec![],
+    }
+}
+
+/// Files and directories starting with `prefix`, directories end with `/`.
+fn files(prefix: &str) -> Vec<String> {
+    let (dir, base) = match prefix.rfind('/') {
+        Some(i) => (&prefix[..i + 1], &prefix[i + 1..]),
+        None => ("", prefix),
+    };
+    let entries = match std::fs::read_dir(if dir.is_empty() { "." } else { dir }) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut files = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(base) || (name.starts_with('.') && !base.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            Some(format!("{}{}{}", dir, name, if is_dir { "/" } else { "" }))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values()
+}
+
+/// The option `word` of `command`, or the global `--home`.
+fn find_option<'a>(command: &'a Command, root: &'a Command, word: &str) -> Option<&'a Arg> {
+    let word = word.split('=').next().unwrap();
+    let matches = |arg: &&Arg| match word.strip_prefix("--") {
+        Some(long) => arg.get_long() == Some(long),
+        None => word.len() == 2 && arg.get_short() == word.chars().nth(1),
+    };
+    command.get_arguments().find(matches).or_else(|| {
+        root.get_arguments()
+            .filter(|a| a.is_global_set())
+            .find(matches)
+    })
+}
+
+fn arg_values(config: &BingoConfig, arg: &Arg, cur: &str, positionals: &[&str]) -> Vec<String> {
+    let possible = arg.get_possible_values();
+    if !possible.is_empty() {
+        return possible.iter().map(|v| v.get_name().to_string()).collect();
+    }
+    let name = positionals.first().copied();
+    match arg.get_id().as_str() {
+        "target" if cur.starts_with('/') => files(cur),
+        "name" | "names" | "old" | "target" => executable_names(config),
+        "version" => versions(config, name),
+        "tag" | "tags" => tags(config),
+        "keys" => match name.and_then(|name| config.get_executable(name).ok()) {
+            Some(e) => e.env.keys().cloned().collect(),
+            None => vec![],
+        },
+        "path" | "paths" | "files" | "dir" | "home" => files(cur),
+        _ => vec![],
+    }
+}
+
+/// Complete the last of `words`, the arguments of bingo up to and including
+/// the word under the cursor.
+pub fn complete(root: &Command, config: &BingoConfig, words: &[String]) -> Completion {
+    let (cur, done) = match words.split_last() {
+        Some((cur, done)) => (cur.as_str(), done),
+        None => ("", &words[..0]),
+    };
+    let mut command = root;
+    let mut positionals: Vec<&str> = vec![];
+    let mut name_index = None;
+    let mut pending: Option<&Arg> = None;
+    let mut only_positionals = false;
+    for (index, word) in done.iter().enumerate() {
+        if pending.take().is_some() {
+            continue;
+        }
+        if !only_positionals && word == "--" {
+            only_positionals = true;
+            continue;
+        }
+        if !only_positionals && word.starts_with('-') && word.len() > 1 {
+            if let Some(arg) = find_option(command, root, word) {
+                if takes_value(arg) && !word.contains('=') {
+                    pending = Some(arg);
+                }
+            }
+            continue;
+        }
+        if positionals.is_empty() {
+            if let Some(sub) = command.find_subcommand(word) {
+                command = sub;
+                continue;
+            }
+            // `bingo <name> args...`
+            if std::ptr::eq(command, root) {
+                return match config.get_executable(word) {
+                    Ok(_) => Completion::Delegate(index),
+                    Err(_) => Completion::Words(vec![]),
+                };
+            }
+        }
+        if command.get_name() == "run" && name_index.is_some() {
+            break;
+        }
+        if positionals.is_empty() {
+            name_index = Some(index);
+        }
+        positionals.push(word);
+    }
+    if command.get_name() == "run" {
+        if let Some(index) = name_index {
+            return Completion::Delegate(index);
+        }
+    }
+
+    let candidates = if let Some(arg) = pending {
+        arg_values(config, arg, cur, &positionals)
+    } else if cur.starts_with('-') && !only_positionals {
+        let globals = root.get_arguments().filter(|a| a.is_global_set());
+        command
+            .get_arguments()
+            .chain(globals)
+            .filter_map(|a| a.get_long())
+            .map(|long| format!("--{}", long))
+            .collect()
+    } else if positionals.is_empty() && command.has_subcommands() {
+        let mut candidates = command
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| c.get_name().to_string())
+            .collect::<Vec<_>>();
+        if std::ptr::eq(command, root) {
+            candidates.extend(executable_names(config));
+        }
+        candidates
+    } else {
+        let args = command.get_positionals().collect::<Vec<_>>();
+        let arg = args.get(positionals.len()).or_else(|| {
+            args.last()
+                .filter(|a| a.get_num_args().is_some_and(|n| n.max_values() > 1))
+        });
+        match arg {
+            Some(arg) => arg_values(config, arg, cur, &positionals),
+            None => vec![],
+        }
+    };
+    Completion::Words(
+        candidates
+            .into_iter()
+            .filter(|c| c.starts_with(cur))
+            .collect(),
+    )
+}
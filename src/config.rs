@@ -1,39 +1,30 @@
 use crate::error::{BingoError, BingoResult};
+use crate::lock::ConfigLock;
+use crate::metadata::Metadata;
+use crate::naming::{self, NameRule};
+use crate::paths::BingoPaths;
+use colored::*;
 use serde::{Deserialize, Serialize};
-use std::{os::unix::fs::PermissionsExt, path::Path};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
 
-static CONFIG_DIR: &str = ".bingo";
-static CONFIG_FILE: &str = "bingo.json";
-static CONFIG_VERSION: &str = env!("CARGO_PKG_VERSION");
-
-pub fn get_config_dir() -> String {
-    let home = std::env::var("HOME").unwrap();
-    format!("{}/{}", home, CONFIG_DIR)
-}
-
-pub fn get_config_file() -> String {
-    let config_dir = get_config_dir();
-    format!("{}/{}", config_dir, CONFIG_FILE)
-}
-
-pub fn get_bingo_bin_dir() -> String {
-    let config_dir = get_config_dir();
-    format!("{}/bin", config_dir)
-}
-
-fn create_config_dir(path: &str) {
-    let path = std::path::Path::new(path);
-    if path.exists() {
-        return;
-    } else {
-        std::fs::create_dir_all(path).unwrap();
-    }
-}
+/// Schema version written to the `version` field of `bingo.json`. Bump it
+/// and add a step to `MIGRATIONS` whenever the format changes.
+pub static CONFIG_SCHEMA_VERSION: u32 = 8;
+/// Label given to the existing install when a second version is added.
+static DEFAULT_VERSION: &str = "default";
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub enum ExecutableType {
     Binary,
     LinkBinary,
+    /// Another registered executable, or an absolute path, run with the
+    /// entry's `args` put in front.
+    Alias,
 }
 
 impl From<&str> for ExecutableType {
@@ -41,75 +32,341 @@ impl From<&str> for ExecutableType {
         match s {
             "b" => ExecutableType::Binary,
             "lb" => ExecutableType::LinkBinary,
+            "a" => ExecutableType::Alias,
             _ => ExecutableType::Binary,
         }
     }
 }
 
-impl Into<&'static str> for ExecutableType {
-    fn into(self) -> &'static str {
+impl ExecutableType {
+    /// Name shown to users, also accepted by `ls --type`.
+    pub fn kind(&self) -> &'static str {
         match self {
+            ExecutableType::Binary => "copy",
+            ExecutableType::LinkBinary => "link",
+            ExecutableType::Alias => "alias",
+        }
+    }
+}
+
+impl From<ExecutableType> for &'static str {
+    fn from(t: ExecutableType) -> Self {
+        match t {
             ExecutableType::Binary => "b",
             ExecutableType::LinkBinary => "lb",
+            ExecutableType::Alias => "a",
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How an environment variable is changed before running an executable.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum EnvValue {
+    Set(String),
+    Unset,
+    /// Prepend to the current value, separated by `:` like `PATH`.
+    Prepend(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExecutableVersion {
+    pub label: String,
+    pub path: String,
+    pub executable_type: ExecutableType,
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Executable {
     pub name: String,
     pub path: String,
     pub executable_type: ExecutableType,
+    /// Replace the bingo process with the executable instead of spawning
+    /// it as a child.
+    #[serde(default = "default_exec")]
+    pub exec: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, EnvValue>,
+    /// Arguments passed before the ones given on the command line.
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Label of the active version, `path` and `executable_type` mirror it.
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub versions: Vec<ExecutableVersion>,
+    /// Mirrors the active version, except for the description.
+    #[serde(default)]
+    pub metadata: Metadata,
+    /// Free-form labels used to group executables.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Kept in the registry, but the bin dir entry is moved to the disabled
+    /// dir.
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+fn default_exec() -> bool {
+    true
+}
+
+impl Executable {
+    pub fn new(name: &str, path: &str, executable_type: ExecutableType) -> Executable {
+        Executable {
+            name: name.to_string(),
+            path: path.to_string(),
+            executable_type,
+            exec: default_exec(),
+            env: BTreeMap::new(),
+            args: Vec::new(),
+            cwd: None,
+            version: None,
+            versions: Vec::new(),
+            metadata: Metadata::default(),
+            tags: Vec::new(),
+            disabled: false,
+        }
+    }
+
+    pub fn get_version(&self, label: &str) -> BingoResult<&ExecutableVersion> {
+        match self.versions.iter().find(|v| v.label == label) {
+            Some(v) => Ok(v),
+            None => Err(BingoError::VersionNotFound(format!(
+                "{}@{}",
+                self.name, label
+            ))),
+        }
+    }
+
+    fn apply_env(&self, command: &mut std::process::Command) {
+        for (key, value) in &self.env {
+            match value {
+                EnvValue::Set(v) => {
+                    command.env(key, v);
+                }
+                EnvValue::Unset => {
+                    command.env_remove(key);
+                }
+                EnvValue::Prepend(v) => match std::env::var(key) {
+                    Ok(current) if !current.is_empty() => {
+                        command.env(key, format!("{}:{}", v, current));
+                    }
+                    _ => {
+                        command.env(key, v);
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Build the command for a chain returned by `BingoConfig::resolve`. The
+/// target's default arguments come first, then those of each alias from the
+/// innermost outwards, then `args`. Settings of outer aliases win.
+pub fn build_command(chain: &[&Executable], args: Vec<String>) -> std::process::Command {
+    let target = chain[chain.len() - 1];
+    let mut command = std::process::Command::new(&target.path);
+    for e in chain.iter().rev() {
+        command.args(&e.args);
+        e.apply_env(&mut command);
+        if let Some(cwd) = &e.cwd {
+            command.current_dir(cwd);
+        }
+    }
+    command.args(args);
+    command
+}
+
+/// Split a `KEY=VALUE` argument.
+pub fn parse_env_var(s: &str) -> BingoResult<(String, String)> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(BingoError::InvalidEnvVar(s.to_string())),
+    }
+}
+
+/// User preferences, changed with `bingo config set`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Settings {
+    /// Refuse to run an executable whose installed file no longer matches
+    /// the recorded checksum.
+    #[serde(default)]
+    pub strict: bool,
+    /// How `cp`/`ln` derive names, see `naming::NameRule`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_rule: Option<String>,
+}
+
+fn parse_bool(key: &str, value: &str) -> BingoResult<bool> {
+    match value {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(BingoError::InvalidSetting(format!("{}={}", key, value))),
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Settings {
+    pub const KEYS: &'static [&'static str] = &["strict", "name-rule"];
+
+    pub fn get(&self, key: &str) -> BingoResult<String> {
+        match key {
+            "strict" => Ok(self.strict.to_string()),
+            "name-rule" => Ok(self.name_rule().to_string()),
+            _ => Err(BingoError::UnknownSetting(key.to_string())),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> BingoResult<()> {
+        match key {
+            "strict" => self.strict = parse_bool(key, value)?,
+            "name-rule" => match naming::parse_rules(value) {
+                Some(_) => self.name_rule = Some(value.to_string()),
+                None => return Err(BingoError::InvalidSetting(format!("{}={}", key, value))),
+            },
+            _ => return Err(BingoError::UnknownSetting(key.to_string())),
+        }
+        Ok(())
+    }
+
+    pub fn name_rule(&self) -> &str {
+        self.name_rule.as_deref().unwrap_or(NameRule::DEFAULT)
+    }
+
+    pub fn name_rules(&self) -> Vec<NameRule> {
+        naming::parse_rules(self.name_rule()).unwrap_or(vec![NameRule::Stem])
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BingoConfig {
     pub version: String,
+    #[serde(default)]
+    pub settings: Settings,
     pub executables: Vec<Executable>,
+    /// Entries that could not be read, kept as they are so saving does not
+    /// lose them.
+    #[serde(skip)]
+    pub invalid_executables: Vec<serde_json::Value>,
+    /// Problems found while parsing, printed by `BingoConfigFile::load`.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// The top level of `bingo.json`, entries are parsed one by one so a single
+/// bad entry does not make the whole registry unreadable.
+#[derive(Deserialize)]
+struct RawBingoConfig<'a> {
+    version: String,
+    #[serde(default)]
+    settings: Settings,
+    #[serde(borrow)]
+    executables: Vec<&'a serde_json::value::RawValue>,
+}
+
+/// Line and column of byte `offset` in `s`, both starting at 1.
+fn line_column(s: &str, offset: usize) -> (usize, usize) {
+    let before = &s[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(i) => offset - i,
+        None => offset + 1,
+    };
+    (line, column)
+}
+
+/// Turn a deserialization error of the document starting at byte `offset`
+/// of `json` into a `ConfigParseError`.
+fn parse_error(
+    json: &str,
+    offset: usize,
+    prefix: &str,
+    err: serde_path_to_error::Error<serde_json::Error>,
+) -> BingoError {
+    let path = match (prefix, err.path().to_string().as_str()) {
+        ("", ".") => ".".to_string(),
+        (prefix, ".") => prefix.to_string(),
+        ("", path) => path.to_string(),
+        (prefix, path) => format!("{}.{}", prefix, path),
+    };
+    let inner = err.into_inner();
+    // serde_json reports positions relative to the parsed slice
+    let (base_line, base_column) = line_column(json, offset);
+    let line = base_line + inner.line() - 1;
+    let column = if inner.line() == 1 {
+        base_column + inner.column() - 1
+    } else {
+        inner.column()
+    };
+    let message = inner.to_string();
+    let suffix = format!(" at line {} column {}", inner.line(), inner.column());
+    let message = message
+        .strip_suffix(&suffix)
+        .unwrap_or(&message)
+        .to_string();
+    BingoError::ConfigParseError {
+        path,
+        line,
+        column,
+        message,
+    }
 }
 
 impl BingoConfig {
     pub fn new() -> BingoConfig {
-        // init bingo dir
-        create_config_dir(&get_bingo_bin_dir());
         BingoConfig {
             version: String::new(),
+            settings: Settings::default(),
             executables: Vec::new(),
+            invalid_executables: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Parse `bingo.json`. Entries of `executables` that cannot be read are
+    /// skipped with a warning and kept aside in `invalid_executables`.
     pub fn from_json(json: &str) -> BingoResult<BingoConfig> {
         let mut config = BingoConfig::new();
-        let data = serde_json::from_str(json);
-        let data: serde_json::Value = match data {
-            Ok(data) => data,
-            Err(e) => {
-                let e = BingoError::ConfigFileError(e.to_string());
-                return Err(e);
-            }
+        let mut de = serde_json::Deserializer::from_str(json);
+        let raw: RawBingoConfig = match serde_path_to_error::deserialize(&mut de) {
+            Ok(raw) => raw,
+            Err(err) => return Err(parse_error(json, 0, "", err)),
         };
-        let version = data["version"].as_str().unwrap();
-        config.version = version.to_string();
-        let executables = data["executables"].as_array().unwrap();
-        for executable in executables {
-            let name = executable["name"].as_str().unwrap();
-            let path = executable["path"].as_str().unwrap();
-            let executable_type = match executable["executable_type"].as_str().unwrap() {
-                "Binary" => ExecutableType::Binary,
-                "LinkBinary" => ExecutableType::LinkBinary,
-                _ => ExecutableType::Binary,
-            };
-            config.executables.push(Executable {
-                name: name.to_string(),
-                path: path.to_string(),
-                executable_type,
-            });
+        config.version = raw.version;
+        config.settings = raw.settings;
+        for (index, executable) in raw.executables.iter().enumerate() {
+            let entry = executable.get();
+            let offset = entry.as_ptr() as usize - json.as_ptr() as usize;
+            let mut de = serde_json::Deserializer::from_str(entry);
+            match serde_path_to_error::deserialize::<_, Executable>(&mut de) {
+                Ok(e) => config.executables.push(e),
+                Err(err) => {
+                    let prefix = format!("executables[{}]", index);
+                    let err = parse_error(json, offset, &prefix, err);
+                    config.warnings.push(format!("skipping entry: {}", err));
+                    // a raw value is always valid JSON
+                    config
+                        .invalid_executables
+                        .push(serde_json::from_str(entry).unwrap());
+                }
+            }
         }
         Ok(config)
     }
 
     pub fn to_json(&self) -> BingoResult<String> {
-        let s = serde_json::to_string_pretty(self);
+        let s = if self.invalid_executables.is_empty() {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_value(self).and_then(|mut value| {
+                let executables = value["executables"].as_array_mut().unwrap();
+                executables.extend(self.invalid_executables.iter().cloned());
+                serde_json::to_string_pretty(&value)
+            })
+        };
         match s {
             Ok(s) => Ok(s),
             Err(e) => {
@@ -120,6 +377,178 @@ impl BingoConfig {
     }
 }
 
+/// One step of the migration chain, upgrading a document from schema `from`
+/// to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    migrate: fn(&mut serde_json::Value),
+}
+
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "add exec, env, args, cwd and versions to every executable",
+        migrate: migrate_v1,
+    },
+    Migration {
+        from: 2,
+        description:
+            "add metadata to every executable and version, filled in from the installed files",
+        migrate: migrate_v2,
+    },
+    Migration {
+        from: 3,
+        description: "add settings with strict mode off",
+        migrate: migrate_v3,
+    },
+    Migration {
+        from: 4,
+        description: "add tags to every executable",
+        migrate: migrate_v4,
+    },
+    Migration {
+        from: 5,
+        description: "mark every executable as enabled",
+        migrate: migrate_v5,
+    },
+    Migration {
+        from: 6,
+        description: "allow a name-rule setting, unset keeps the stem rule",
+        migrate: migrate_v6,
+    },
+    Migration {
+        from: 7,
+        description: "allow nanoseconds of source mtimes, unknown until the next update check",
+        migrate: migrate_v7,
+    },
+];
+
+fn migrate_v1(doc: &mut serde_json::Value) {
+    let defaults = [
+        ("exec", serde_json::json!(true)),
+        ("env", serde_json::json!({})),
+        ("args", serde_json::json!([])),
+        ("cwd", serde_json::Value::Null),
+        ("version", serde_json::Value::Null),
+        ("versions", serde_json::json!([])),
+    ];
+    if let Some(executables) = doc["executables"].as_array_mut() {
+        for executable in executables.iter_mut().filter_map(|e| e.as_object_mut()) {
+            for (key, value) in &defaults {
+                executable
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+/// Adds empty metadata, `BingoConfig::backfill_metadata` fills it in once
+/// the bin dir is known.
+fn migrate_v2(doc: &mut serde_json::Value) {
+    if let Some(executables) = doc["executables"].as_array_mut() {
+        for executable in executables.iter_mut().filter_map(|e| e.as_object_mut()) {
+            if let Some(versions) = executable
+                .get_mut("versions")
+                .and_then(|v| v.as_array_mut())
+            {
+                for version in versions.iter_mut().filter_map(|v| v.as_object_mut()) {
+                    version
+                        .entry("metadata")
+                        .or_insert_with(|| serde_json::json!({}));
+                }
+            }
+            executable
+                .entry("metadata")
+                .or_insert_with(|| serde_json::json!({}));
+        }
+    }
+}
+
+fn migrate_v3(doc: &mut serde_json::Value) {
+    if let Some(doc) = doc.as_object_mut() {
+        doc.entry("settings")
+            .or_insert_with(|| serde_json::json!({ "strict": false }));
+    }
+}
+
+fn migrate_v4(doc: &mut serde_json::Value) {
+    if let Some(executables) = doc["executables"].as_array_mut() {
+        for executable in executables.iter_mut().filter_map(|e| e.as_object_mut()) {
+            executable
+                .entry("tags")
+                .or_insert_with(|| serde_json::json!([]));
+        }
+    }
+}
+
+fn migrate_v5(doc: &mut serde_json::Value) {
+    if let Some(executables) = doc["executables"].as_array_mut() {
+        for executable in executables.iter_mut().filter_map(|e| e.as_object_mut()) {
+            executable
+                .entry("disabled")
+                .or_insert_with(|| serde_json::json!(false));
+        }
+    }
+}
+
+/// Nothing to change, a missing `name_rule` means the old behaviour.
+fn migrate_v6(_doc: &mut serde_json::Value) {}
+
+/// Nothing to change, a missing `source_mtime_nanos` makes the next
+/// `update` or `outdated` hash the source once and record it.
+fn migrate_v7(_doc: &mut serde_json::Value) {}
+
+/// Schema version of a document. Releases before schema versioning wrote
+/// their package version, like `0.2.1`, which is schema 1.
+fn schema_version(version: &str) -> BingoResult<u32> {
+    if version.contains('.') {
+        return Ok(1);
+    }
+    match version.parse::<u32>() {
+        Ok(v) => Ok(v),
+        Err(_) => Err(BingoError::UnsupportedConfigVersion(version.to_string())),
+    }
+}
+
+/// Result of running the migration chain over a document.
+pub struct MigratedConfig {
+    pub from: u32,
+    pub json: String,
+    pub steps: Vec<&'static Migration>,
+}
+
+/// Upgrade `json` to `CONFIG_SCHEMA_VERSION` step by step. Returns `None`
+/// if it is already current, or cannot be parsed at all, in which case
+/// `from_json` reports the error.
+pub fn migrate_json(json: &str) -> BingoResult<Option<MigratedConfig>> {
+    let mut doc: serde_json::Value = match serde_json::from_str(json) {
+        Ok(doc) => doc,
+        Err(_) => return Ok(None),
+    };
+    let from = match doc["version"].as_str() {
+        Some(version) => schema_version(version)?,
+        None => return Ok(None),
+    };
+    if from > CONFIG_SCHEMA_VERSION {
+        return Err(BingoError::ConfigTooNew(from, CONFIG_SCHEMA_VERSION));
+    }
+    if from == CONFIG_SCHEMA_VERSION {
+        return Ok(None);
+    }
+    let mut steps = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        (migration.migrate)(&mut doc);
+        doc["version"] = serde_json::json!((migration.from + 1).to_string());
+        steps.push(migration);
+    }
+    match serde_json::to_string_pretty(&doc) {
+        Ok(json) => Ok(Some(MigratedConfig { from, json, steps })),
+        Err(err) => Err(BingoError::ConfigFileError(err.to_string())),
+    }
+}
+
 impl Default for BingoConfig {
     fn default() -> Self {
         BingoConfig::new()
@@ -129,7 +558,7 @@ impl Default for BingoConfig {
 fn link_file(src: &str, dest: &str) -> BingoResult<()> {
     let src = std::path::Path::new(src);
     let dest = std::path::Path::new(dest);
-    if dest.exists() {
+    if dest.exists() || dest.is_symlink() {
         match std::fs::remove_file(dest) {
             Ok(_) => (),
             Err(err) => {
@@ -168,50 +597,277 @@ fn copy_file(src: &str, dest: &str) -> BingoResult<()> {
     }
 }
 
+/// Quote `s` for a POSIX shell.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// The running bingo and the arguments selecting `paths`, for scripts that
+/// call back into it.
+pub fn bingo_command(paths: &BingoPaths) -> std::io::Result<Vec<String>> {
+    let bingo = std::env::current_exe()?;
+    let mut command = vec![bingo.to_string_lossy().to_string()];
+    if let Some(home) = &paths.home {
+        command.push("--home".to_string());
+        command.push(home.clone());
+    }
+    Ok(command)
+}
+
+/// Aliases are installed as a small script calling back into bingo, so they
+/// work from `$PATH` and always follow the current registry.
+fn write_alias_wrapper(paths: &BingoPaths, name: &str, dest: &str) -> BingoResult<()> {
+    let bingo = match bingo_command(paths) {
+        Ok(bingo) => bingo,
+        Err(err) => return Err(BingoError::AliasError(err.to_string())),
+    };
+    let bingo = bingo
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let script = format!(
+        "#!/bin/sh\n# generated by bingo, do not edit\nexec {} run -- {} \"$@\"\n",
+        bingo,
+        shell_quote(name)
+    );
+    let result = std::fs::write(dest, script)
+        .and_then(|_| std::fs::set_permissions(dest, std::fs::Permissions::from_mode(0o755)));
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(BingoError::AliasError(err.to_string())),
+    }
+}
+
+/// Check that `path` is an executable file and make it absolute.
+fn check_executable(path: &Path) -> BingoResult<std::path::PathBuf> {
+    // check if path exists
+    if !path.exists() {
+        let e = BingoError::FileNotFound(path.to_str().unwrap().to_string());
+        return Err(e);
+    }
+
+    // check if path is a file
+    if !path.is_file() {
+        let e = BingoError::ExecutableNotFile(path.to_str().unwrap().to_string());
+        return Err(e);
+    }
+
+    let mut path = path.to_path_buf();
+    // convert path to absolute path
+    if !path.is_absolute() {
+        path = std::env::current_dir().unwrap().join(path);
+    }
+
+    // check if path is executable
+    if path.is_symlink() {
+        if !path.symlink_metadata().unwrap().permissions().mode() & 0o111 != 0 {
+            let e = BingoError::ExecutableNotExecutable(path.to_str().unwrap().to_string());
+            return Err(e);
+        }
+    } else {
+        if !path.metadata().unwrap().permissions().mode() & 0o111 != 0 {
+            let e = BingoError::ExecutableNotExecutable(path.to_str().unwrap().to_string());
+            return Err(e);
+        }
+    }
+    Ok(path)
+}
+
+#[derive(Debug)]
+enum Undo {
+    /// A path created by the transaction.
+    Created(PathBuf),
+    /// A path moved aside to a backup next to it.
+    MovedAside {
+        path: PathBuf,
+        backup: PathBuf,
+    },
+    Renamed {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+/// Filesystem changes staged alongside a config change. Every change is
+/// journaled so the bin dir can be put back exactly as it was if a later
+/// step, or saving the config, fails. Replaced and removed files are only
+/// moved aside and deleted on `commit`.
+#[derive(Debug)]
+pub struct Transaction {
+    pub paths: BingoPaths,
+    journal: Vec<Undo>,
+}
+
+fn fs_error(path: &Path, err: std::io::Error) -> BingoError {
+    BingoError::FileSystemError(format!("{}: {}", path.display(), err))
+}
+
+impl Transaction {
+    pub fn new(paths: BingoPaths) -> Transaction {
+        Transaction {
+            paths,
+            journal: Vec::new(),
+        }
+    }
+
+    /// Move `path` out of the way if it exists. It comes back on rollback.
+    pub fn remove(&mut self, path: &str) -> BingoResult<()> {
+        let path = PathBuf::from(path);
+        if std::fs::symlink_metadata(&path).is_err() {
+            return Ok(());
+        }
+        let mut backup = path.clone();
+        backup.set_file_name(format!(
+            ".{}.bingo-{}",
+            path.file_name().unwrap().to_str().unwrap(),
+            std::process::id()
+        ));
+        if let Err(err) = std::fs::rename(&path, &backup) {
+            return Err(fs_error(&path, err));
+        }
+        self.journal.push(Undo::MovedAside { path, backup });
+        Ok(())
+    }
+
+    /// Create `path` with `f`, replacing whatever was there.
+    pub fn create<F>(&mut self, path: &str, f: F) -> BingoResult<()>
+    where
+        F: FnOnce() -> BingoResult<()>,
+    {
+        self.remove(path)?;
+        self.journal.push(Undo::Created(PathBuf::from(path)));
+        f()
+    }
+
+    /// Create `path` and its missing parents, all of which go away again
+    /// on rollback.
+    pub fn create_dir(&mut self, path: &str) -> BingoResult<()> {
+        let missing = Path::new(path)
+            .ancestors()
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .map(Path::to_path_buf)
+            .collect::<Vec<_>>();
+        for dir in missing.into_iter().rev() {
+            if let Err(err) = std::fs::create_dir(&dir) {
+                return Err(fs_error(&dir, err));
+            }
+            self.journal.push(Undo::Created(dir));
+        }
+        Ok(())
+    }
+
+    /// Rename `from` to `to`, replacing whatever was at `to`.
+    pub fn rename(&mut self, from: &str, to: &str) -> BingoResult<()> {
+        self.remove(to)?;
+        let (from, to) = (PathBuf::from(from), PathBuf::from(to));
+        if let Err(err) = std::fs::rename(&from, &to) {
+            return Err(fs_error(&from, err));
+        }
+        self.journal.push(Undo::Renamed { from, to });
+        Ok(())
+    }
+
+    /// Keep the changes and delete what was moved aside.
+    pub fn commit(self) {
+        for undo in self.journal {
+            if let Undo::MovedAside { backup, .. } = undo {
+                remove_path(&backup);
+            }
+        }
+    }
+
+    /// Undo the changes in reverse order.
+    pub fn rollback(self) {
+        for undo in self.journal.into_iter().rev() {
+            let result = match &undo {
+                Undo::Created(path) => {
+                    remove_path(path);
+                    Ok(())
+                }
+                Undo::MovedAside { path, backup } => std::fs::rename(backup, path),
+                Undo::Renamed { from, to } => std::fs::rename(to, from),
+            };
+            if let Err(err) = result {
+                eprintln!(
+                    "{} rollback of {:?} failed: {}",
+                    "warning:".yellow(),
+                    undo,
+                    err
+                );
+            }
+        }
+    }
+}
+
+fn remove_path(path: &Path) {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => {
+            let _ = std::fs::remove_dir_all(path);
+        }
+        Ok(_) => {
+            let _ = std::fs::remove_file(path);
+        }
+        Err(_) => {}
+    }
+}
+
 impl BingoConfig {
     pub fn set_version(&mut self) {
-        self.version = CONFIG_VERSION.to_string();
+        self.version = CONFIG_SCHEMA_VERSION.to_string();
     }
 
     pub fn install_executables(
+        txn: &mut Transaction,
         path: &str,
         name: &str,
         executable_type: ExecutableType,
     ) -> BingoResult<()> {
-        let config_dir = get_bingo_bin_dir();
-        let dest = format!("{}/{}", config_dir, name);
-        match executable_type {
-            ExecutableType::Binary => copy_file(path, &dest)?,
-            ExecutableType::LinkBinary => link_file(path, &dest)?,
-        }
+        let dest = txn.paths.bin_file(name);
+        BingoConfig::install_to(txn, &dest, path, name, executable_type)
+    }
+
+    fn install_to(
+        txn: &mut Transaction,
+        dest: &str,
+        path: &str,
+        name: &str,
+        executable_type: ExecutableType,
+    ) -> BingoResult<()> {
+        let dest = dest.to_string();
+        let paths = txn.paths.clone();
+        txn.create(&dest, || match executable_type {
+            ExecutableType::Binary => copy_file(path, &dest),
+            ExecutableType::LinkBinary => link_file(path, &dest),
+            ExecutableType::Alias => write_alias_wrapper(&paths, name, &dest),
+        })?;
         // make executable
         let dest = std::path::Path::new(&dest);
         if let Ok(perms) = match executable_type {
-            ExecutableType::Binary => dest.metadata(),
             ExecutableType::LinkBinary => dest.symlink_metadata(),
+            _ => dest.metadata(),
         } {
             perms.permissions().set_mode(0o777);
-            return Ok(());
+            Ok(())
         } else {
             let e = BingoError::PermissionDenied(dest.to_str().unwrap().to_string());
-            return Err(e);
+            Err(e)
         }
     }
 
-    pub fn uninstall_executables(name: &str) {
-        let config_dir = get_bingo_bin_dir();
-        let dest = format!("{}/{}", config_dir, name);
-        if std::path::Path::new(&dest).exists() {
-            std::fs::remove_file(dest).unwrap();
-        }
+    pub fn uninstall_executables(txn: &mut Transaction, name: &str) -> BingoResult<()> {
+        let dest = txn.paths.bin_file(name);
+        txn.remove(&dest)
     }
 
     pub fn add_executable(
         &mut self,
+        txn: &mut Transaction,
         path: &Path,
         name: &str,
         executable_type: ExecutableType,
-    ) -> BingoResult<()> {
+    ) -> BingoResult<&mut Executable> {
+        naming::check_name(name)?;
         // check if name already exists
         for executable in &self.executables {
             if executable.name == name {
@@ -219,64 +875,439 @@ impl BingoConfig {
                 return Err(e);
             }
         }
-        // check if path exists
-        if !path.exists() {
-            let e = BingoError::FileNotFound(path.to_str().unwrap().to_string());
-            return Err(e);
+        let path = check_executable(path)?;
+
+        let path = path.to_str().unwrap();
+        let mut executable = Executable::new(name, path, executable_type);
+        BingoConfig::install_executables(txn, path, name, executable_type)?;
+        executable.metadata = Metadata::collect(path, &txn.paths.bin_file(name))?;
+        self.executables.push(executable);
+
+        Ok(self.executables.last_mut().unwrap())
+    }
+
+    /// Install `path` as version `label` of `name` and make it the active one.
+    /// An existing unversioned entry keeps its current install as version
+    /// `default`.
+    pub fn add_version(
+        &mut self,
+        txn: &mut Transaction,
+        path: &Path,
+        name: &str,
+        label: &str,
+        executable_type: ExecutableType,
+    ) -> BingoResult<&mut Executable> {
+        if label.is_empty() || label.contains('/') || label.starts_with('.') {
+            return Err(BingoError::InvalidVersion(label.to_string()));
         }
+        if self.get_executable(name).is_err() {
+            naming::check_name(name)?;
+        }
+        let path = check_executable(path)?;
+        let path = path.to_str().unwrap();
 
-        // check if path is a file
-        if !path.is_file() {
-            let e = BingoError::ExecutableNotFile(path.to_str().unwrap().to_string());
-            return Err(e);
+        let version_dir = txn.paths.version_dir(name);
+        txn.create_dir(&version_dir)?;
+        let existing = self.executables.iter().position(|e| e.name == name);
+        if let Some(index) = existing {
+            let executable = &mut self.executables[index];
+            if matches!(executable.executable_type, ExecutableType::Alias) {
+                return Err(BingoError::DuplicateExecutableName(name.to_string()));
+            }
+            if executable.versions.iter().any(|v| v.label == label)
+                || (executable.versions.is_empty() && label == DEFAULT_VERSION)
+            {
+                return Err(BingoError::DuplicateVersion(format!("{}@{}", name, label)));
+            }
+            if executable.versions.is_empty() {
+                // move the current install into the versions dir
+                let old = txn.paths.bin_file(name);
+                let new = txn.paths.version_file(name, DEFAULT_VERSION);
+                txn.rename(&old, &new)?;
+                executable.versions.push(ExecutableVersion {
+                    label: DEFAULT_VERSION.to_string(),
+                    path: executable.path.clone(),
+                    executable_type: executable.executable_type,
+                    metadata: executable.metadata.clone(),
+                });
+                executable.version = Some(DEFAULT_VERSION.to_string());
+            }
         }
 
-        let mut path = path.to_path_buf();
-        // convert path to absolute path
-        if !path.is_absolute() {
-            path = std::env::current_dir().unwrap().join(path);
+        let dest = txn.paths.version_file(name, label);
+        txn.create(&dest, || match executable_type {
+            ExecutableType::LinkBinary => link_file(path, &dest),
+            _ => copy_file(path, &dest),
+        })?;
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                self.executables
+                    .push(Executable::new(name, path, executable_type));
+                self.executables.len() - 1
+            }
+        };
+        self.executables[index].versions.push(ExecutableVersion {
+            label: label.to_string(),
+            path: path.to_string(),
+            executable_type,
+            metadata: Metadata::collect(path, &dest)?,
+        });
+        self.use_version(txn, name, label)
+    }
+
+    /// Point the bin dir entry of `name` at version `label`.
+    pub fn use_version(
+        &mut self,
+        txn: &mut Transaction,
+        name: &str,
+        label: &str,
+    ) -> BingoResult<&mut Executable> {
+        let executable = self.get_executable_mut(name)?;
+        let version = executable.get_version(label)?.clone();
+        let bin = txn.paths.entry_file(name, executable.disabled);
+        let file = txn.paths.version_file(name, label);
+        txn.create(&bin, || link_file(&file, &bin))?;
+        executable.version = Some(version.label);
+        executable.path = version.path;
+        executable.executable_type = version.executable_type;
+        let description = executable.metadata.description.take();
+        executable.metadata = Metadata {
+            description,
+            ..version.metadata
+        };
+        Ok(executable)
+    }
+
+    pub fn remove_version(
+        &mut self,
+        txn: &mut Transaction,
+        name: &str,
+        label: &str,
+    ) -> BingoResult<()> {
+        let executable = self.get_executable_mut(name)?;
+        executable.get_version(label)?;
+        if executable.versions.len() == 1 {
+            return self.remove_executable(txn, name);
+        }
+        if executable.version.as_deref() == Some(label) {
+            return Err(BingoError::ActiveVersion(format!("{}@{}", name, label)));
         }
+        executable.versions.retain(|v| v.label != label);
+        let file = txn.paths.version_file(name, label);
+        txn.remove(&file)
+    }
 
-        // check if path is executable
-        if path.is_symlink() {
-            if !path.symlink_metadata().unwrap().permissions().mode() & 0o111 != 0 {
-                let e = BingoError::ExecutableNotExecutable(path.to_str().unwrap().to_string());
-                return Err(e);
+    pub fn add_alias(
+        &mut self,
+        txn: &mut Transaction,
+        name: &str,
+        target: &str,
+        args: Vec<String>,
+    ) -> BingoResult<&mut Executable> {
+        naming::check_name(name)?;
+        if self.executables.iter().any(|e| e.name == name) {
+            return Err(BingoError::DuplicateExecutableName(name.to_string()));
+        }
+        if target.starts_with('/') {
+            if !Path::new(target).is_file() {
+                return Err(BingoError::FileNotFound(target.to_string()));
             }
         } else {
-            if !path.metadata().unwrap().permissions().mode() & 0o111 != 0 {
-                let e = BingoError::ExecutableNotExecutable(path.to_str().unwrap().to_string());
-                return Err(e);
+            self.get_executable(target)?;
+        }
+
+        let mut executable = Executable::new(name, target, ExecutableType::Alias);
+        executable.args = args;
+        executable.metadata = Metadata::installed_now();
+        BingoConfig::install_executables(txn, target, name, ExecutableType::Alias)?;
+        self.executables.push(executable);
+        Ok(self.executables.last_mut().unwrap())
+    }
+
+    /// Recreate the bin dir entry of `name` from the registry, in the
+    /// disabled dir if it is disabled.
+    pub fn reinstall_executable(&self, txn: &mut Transaction, name: &str) -> BingoResult<()> {
+        let executable = self.get_executable(name)?;
+        let dest = txn.paths.entry_file(name, executable.disabled);
+        if executable.disabled {
+            let dir = txn.paths.disabled_dir.clone();
+            txn.create_dir(&dir)?;
+        }
+        if let Some(label) = &executable.version {
+            let file = txn.paths.version_file(name, label);
+            return txn.create(&dest, || link_file(&file, &dest));
+        }
+        let executable_type = executable.executable_type;
+        BingoConfig::install_to(txn, &dest, &executable.path, name, executable_type)
+    }
+
+    /// Take the bin dir entry of `name` off `$PATH`, keeping it in the
+    /// disabled dir so enabling restores exactly what was installed.
+    pub fn disable_executable(&mut self, txn: &mut Transaction, name: &str) -> BingoResult<()> {
+        let executable = self.get_executable_mut(name)?;
+        if executable.disabled {
+            return Ok(());
+        }
+        executable.disabled = true;
+        let bin = txn.paths.bin_file(name);
+        if std::fs::symlink_metadata(&bin).is_err() {
+            return Ok(());
+        }
+        let dir = txn.paths.disabled_dir.clone();
+        txn.create_dir(&dir)?;
+        let file = txn.paths.disabled_file(name);
+        txn.rename(&bin, &file)
+    }
+
+    /// Put the bin dir entry of `name` back, reinstalling it if it got lost
+    /// while disabled.
+    pub fn enable_executable(&mut self, txn: &mut Transaction, name: &str) -> BingoResult<()> {
+        let executable = self.get_executable_mut(name)?;
+        if !executable.disabled {
+            return Ok(());
+        }
+        executable.disabled = false;
+        let file = txn.paths.disabled_file(name);
+        if std::fs::symlink_metadata(&file).is_err() {
+            return self.reinstall_executable(txn, name);
+        }
+        let bin = txn.paths.bin_file(name);
+        txn.rename(&file, &bin)
+    }
+
+    /// Names of the executables tagged `tag`.
+    pub fn tagged(&self, tag: &str) -> BingoResult<Vec<String>> {
+        let names = self
+            .executables
+            .iter()
+            .filter(|e| e.tags.iter().any(|t| t == tag))
+            .map(|e| e.name.clone())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return Err(BingoError::TagNotFound(tag.to_string()));
+        }
+        Ok(names)
+    }
+
+    pub fn add_tag(&mut self, name: &str, tag: &str) -> BingoResult<()> {
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(BingoError::InvalidTag(tag.to_string()));
+        }
+        let executable = self.get_executable_mut(name)?;
+        if !executable.tags.iter().any(|t| t == tag) {
+            executable.tags.push(tag.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn remove_tag(&mut self, name: &str, tag: &str) -> BingoResult<()> {
+        let executable = self.get_executable_mut(name)?;
+        executable.tags.retain(|t| t != tag);
+        Ok(())
+    }
+
+    /// Recreate the installed file of version `label` of `name` from its
+    /// source.
+    pub fn reinstall_version(
+        &self,
+        txn: &mut Transaction,
+        name: &str,
+        label: &str,
+    ) -> BingoResult<()> {
+        let version = self.get_executable(name)?.get_version(label)?;
+        let dest = txn.paths.version_file(name, label);
+        txn.create(&dest, || match version.executable_type {
+            ExecutableType::LinkBinary => link_file(&version.path, &dest),
+            _ => copy_file(&version.path, &dest),
+        })
+    }
+
+    /// Copy the source of `name`, or of its version `label`, again and
+    /// record the new metadata.
+    pub fn refresh_copy(
+        &mut self,
+        txn: &mut Transaction,
+        name: &str,
+        label: Option<&str>,
+    ) -> BingoResult<()> {
+        let installed = match label {
+            Some(label) => {
+                self.reinstall_version(txn, name, label)?;
+                txn.paths.version_file(name, label)
+            }
+            None => {
+                self.reinstall_executable(txn, name)?;
+                txn.paths
+                    .entry_file(name, self.get_executable(name)?.disabled)
             }
+        };
+        let executable = self.get_executable_mut(name)?;
+        let metadata = match label {
+            Some(label) => {
+                let active = executable.version.as_deref() == Some(label);
+                let version = executable.versions.iter_mut().find(|v| v.label == label);
+                let version = version.unwrap();
+                version.metadata = Metadata::collect(&version.path, &installed)?;
+                if !active {
+                    return Ok(());
+                }
+                version.metadata.clone()
+            }
+            None => Metadata::collect(&executable.path, &installed)?,
+        };
+        let description = executable.metadata.description.take();
+        executable.metadata = Metadata {
+            description,
+            ..metadata
+        };
+        Ok(())
+    }
+
+    /// Record the current mtime of the source of `name`, or of its version
+    /// `label`, whose content turned out unchanged.
+    pub fn touch_copy(&mut self, name: &str, label: Option<&str>) -> BingoResult<()> {
+        let executable = self.get_executable_mut(name)?;
+        let source = match label {
+            Some(label) => {
+                let active = executable.version.as_deref() == Some(label);
+                let version = executable.versions.iter_mut().find(|v| v.label == label);
+                let version = version.unwrap();
+                version.metadata.record_source_mtime(&version.path);
+                if !active {
+                    return Ok(());
+                }
+                version.path.clone()
+            }
+            None => executable.path.clone(),
+        };
+        executable.metadata.record_source_mtime(&source);
+        Ok(())
+    }
+
+    /// Register a file that is already in the bin dir. A symlink becomes a
+    /// `LinkBinary` pointing at its target, anything else a `Binary` whose
+    /// source is the file itself.
+    pub fn adopt_executable(
+        &mut self,
+        paths: &BingoPaths,
+        name: &str,
+    ) -> BingoResult<&mut Executable> {
+        if self.executables.iter().any(|e| e.name == name) {
+            return Err(BingoError::DuplicateExecutableName(name.to_string()));
         }
+        let file = paths.bin_file(name);
+        let meta = match std::fs::symlink_metadata(&file) {
+            Ok(meta) => meta,
+            Err(_) => return Err(BingoError::FileNotFound(file)),
+        };
+        let mut executable = if meta.file_type().is_symlink() {
+            let target = match std::fs::read_link(&file) {
+                Ok(target) => {
+                    let target = Path::new(&paths.bin_dir).join(target);
+                    std::fs::canonicalize(&target).unwrap_or(target)
+                }
+                Err(err) => return Err(BingoError::LinkError(err.to_string())),
+            };
+            Executable::new(name, target.to_str().unwrap(), ExecutableType::LinkBinary)
+        } else if meta.is_file() {
+            Executable::new(name, &file, ExecutableType::Binary)
+        } else {
+            return Err(BingoError::ExecutableNotFile(file));
+        };
+        if let Ok(metadata) = Metadata::backfill(&executable.path, &file) {
+            executable.metadata = metadata;
+        }
+        self.executables.push(executable);
+        Ok(self.executables.last_mut().unwrap())
+    }
 
-        // check if executable already exists
+    /// Fill in the metadata of entries written before it was recorded, from
+    /// the files that are installed now. Entries whose files are gone are
+    /// left alone, `bingo doctor` reports them.
+    pub fn backfill_metadata(&mut self, paths: &BingoPaths) {
         for executable in &mut self.executables {
-            if executable.name == name {
-                // uninstall old executable
-                BingoConfig::uninstall_executables(&name);
-                // install new executable
-                BingoConfig::install_executables(path.to_str().unwrap(), name, executable_type)?;
-                // upadte executable
-                executable.path = path.to_str().unwrap().to_string();
-                executable.executable_type = executable_type;
-                executable.name = name.to_string();
-                return Ok(());
+            if matches!(executable.executable_type, ExecutableType::Alias)
+                || executable.metadata.sha256.is_some()
+            {
+                continue;
+            }
+            for version in &mut executable.versions {
+                let file = paths.version_file(&executable.name, &version.label);
+                if let Ok(metadata) = Metadata::backfill(&version.path, &file) {
+                    version.metadata = metadata;
+                }
+            }
+            let active = executable
+                .version
+                .as_ref()
+                .and_then(|label| executable.versions.iter().find(|v| &v.label == label));
+            let metadata = match active {
+                Some(version) => Ok(version.metadata.clone()),
+                None => Metadata::backfill(&executable.path, &paths.bin_file(&executable.name)),
+            };
+            if let Ok(metadata) = metadata {
+                let description = executable.metadata.description.take();
+                executable.metadata = Metadata {
+                    description,
+                    ..metadata
+                };
             }
         }
+    }
 
-        let executable = Executable {
-            name: name.to_string(),
-            path: path.to_str().unwrap().to_string(),
-            executable_type,
+    /// Files in the bin dir without a registry entry, sorted by name.
+    /// Hidden files are skipped, they are leftovers of interrupted
+    /// transactions.
+    pub fn unregistered_files(&self, paths: &BingoPaths) -> Vec<String> {
+        let entries = match std::fs::read_dir(&paths.bin_dir) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
         };
-        BingoConfig::install_executables(path.to_str().unwrap(), name, executable_type)?;
-        self.executables.push(executable);
+        let mut names = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .filter(|name| self.get_executable(name).is_err())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
 
-        Ok(())
+    /// Follow aliases starting at `name`. Returns every entry visited, the
+    /// one that is actually run last.
+    pub fn resolve(&self, name: &str) -> BingoResult<Vec<&Executable>> {
+        let mut chain = vec![self.get_executable(name)?];
+        loop {
+            let e = chain[chain.len() - 1];
+            if !matches!(e.executable_type, ExecutableType::Alias) || e.path.starts_with('/') {
+                return Ok(chain);
+            }
+            let next = self.get_executable(&e.path)?;
+            if chain.iter().any(|c| c.name == next.name) {
+                let mut names = chain.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+                names.push(&next.name);
+                return Err(BingoError::AliasCycle(names.join(" -> ")));
+            }
+            chain.push(next);
+        }
+    }
+
+    pub fn get_executable(&self, name: &str) -> BingoResult<&Executable> {
+        match self.executables.iter().find(|e| e.name == name) {
+            Some(e) => Ok(e),
+            None => Err(BingoError::ExecutableNotFound(name.to_string())),
+        }
     }
 
-    pub fn remove_executable(&mut self, name: &str) -> BingoResult<()> {
+    pub fn get_executable_mut(&mut self, name: &str) -> BingoResult<&mut Executable> {
+        match self.executables.iter_mut().find(|e| e.name == name) {
+            Some(e) => Ok(e),
+            None => Err(BingoError::ExecutableNotFound(name.to_string())),
+        }
+    }
+
+    pub fn remove_executable(&mut self, txn: &mut Transaction, name: &str) -> BingoResult<()> {
         let mut index = 0;
         let mut found = false;
         for (i, executable) in self.executables.iter().enumerate() {
@@ -290,60 +1321,132 @@ impl BingoConfig {
             return Ok(());
         }
         let executable = &self.executables[index];
-        BingoConfig::uninstall_executables(&executable.name);
+        BingoConfig::uninstall_executables(txn, &executable.name)?;
+        let disabled_file = txn.paths.disabled_file(name);
+        txn.remove(&disabled_file)?;
+        let version_dir = txn.paths.version_dir(name);
+        txn.remove(&version_dir)?;
         self.executables.remove(index);
         Ok(())
     }
 
-    pub fn rename_executable(&mut self, old_name: &str, new_name: &str) -> BingoResult<()> {
+    pub fn rename_executable(
+        &mut self,
+        txn: &mut Transaction,
+        old_name: &str,
+        new_name: &str,
+    ) -> BingoResult<()> {
+        naming::check_name(new_name)?;
+        if self.executables.iter().any(|e| e.name == new_name) {
+            return Err(BingoError::DuplicateExecutableName(new_name.to_string()));
+        }
         let mut found = false;
+        let mut is_alias = false;
+        let mut active_version = None;
+        let mut disabled = false;
         for executable in &mut self.executables {
             if executable.name == old_name {
                 found = true;
                 executable.name = new_name.to_string();
+                is_alias = matches!(executable.executable_type, ExecutableType::Alias);
+                active_version = executable.version.clone();
+                disabled = executable.disabled;
                 break;
             }
         }
         if !found {
             return Ok(());
         }
-        let bin_dir_path = get_bingo_bin_dir();
-        let old_path = format!("{}/{}", bin_dir_path, old_name);
-        let new_path = format!("{}/{}", bin_dir_path, new_name);
-        std::fs::rename(old_path, new_path).unwrap();
+        // keep aliases pointing at the renamed executable
+        for executable in &mut self.executables {
+            if matches!(executable.executable_type, ExecutableType::Alias)
+                && executable.path == old_name
+            {
+                executable.path = new_name.to_string();
+            }
+        }
+        let old_path = txn.paths.entry_file(old_name, disabled);
+        let new_path = txn.paths.entry_file(new_name, disabled);
+        if std::fs::symlink_metadata(&old_path).is_ok() {
+            txn.rename(&old_path, &new_path)?;
+        }
+        if is_alias {
+            // the wrapper calls back into bingo by name
+            let paths = txn.paths.clone();
+            txn.create(&new_path, || {
+                write_alias_wrapper(&paths, new_name, &new_path)
+            })?;
+        }
+        if let Some(label) = active_version {
+            let old_dir = txn.paths.version_dir(old_name);
+            let new_dir = txn.paths.version_dir(new_name);
+            txn.rename(&old_dir, &new_dir)?;
+            let file = txn.paths.version_file(new_name, &label);
+            txn.create(&new_path, || link_file(&file, &new_path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `content` to a temporary file next to `path`, fsync it and rename
+/// it over `path`, so readers only ever see the old or the new content.
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = std::path::PathBuf::from(tmp);
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)?;
+        // persist the rename itself
+        if let Some(dir) = path.parent() {
+            std::fs::File::open(dir)?.sync_all()?;
+        }
         Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
     }
+    result
 }
 
 #[derive(Debug)]
 pub struct BingoConfigFile {
-    pub config_dir_path: String,
-    pub config_file_path: String,
+    pub paths: BingoPaths,
     pub config: BingoConfig,
+    /// The loaded config was migrated in memory and not saved yet.
+    migrated: bool,
+    /// Do not print warnings while loading, for shell completion.
+    pub quiet: bool,
 }
 
 impl BingoConfigFile {
-    pub fn new() -> BingoConfigFile {
-        let config_dir_path = get_config_dir();
-        let config_file_path = get_config_file();
-        let config = BingoConfig::new();
+    pub fn new(paths: BingoPaths) -> BingoConfigFile {
         BingoConfigFile {
-            config_dir_path,
-            config_file_path,
-            config,
+            paths,
+            config: BingoConfig::new(),
+            migrated: false,
+            quiet: false,
         }
     }
 
-    pub fn init() -> BingoResult<()> {
+    /// Create the bingo dirs and an empty config if there is none yet.
+    pub fn init(&self) -> BingoResult<()> {
+        for dir in [&self.paths.config_dir, &self.paths.bin_dir] {
+            if let Err(err) = std::fs::create_dir_all(dir) {
+                return Err(BingoError::ConfigFileError(format!("{}: {}", dir, err)));
+            }
+        }
         let mut config = BingoConfig::new();
         config.set_version();
-        let config_file = get_config_file();
-        let path = std::path::Path::new(&config_file);
-        if path.exists() {
+        let path = Path::new(&self.paths.config_file);
+        // a missing config with a backup is recovered by `load`
+        if path.exists() || Path::new(&self.backup_file_path()).exists() {
             return Ok(());
         }
         let config = config.to_json()?;
-        match std::fs::write(config_file, config) {
+        match write_atomic(path, config.as_bytes()) {
             Ok(_) => Ok(()),
             Err(err) => {
                 let e = BingoError::ConfigFileError(err.to_string());
@@ -352,9 +1455,26 @@ impl BingoConfigFile {
         }
     }
 
-    pub fn load(&mut self) -> BingoResult<()> {
-        let config_file_path = self.config_file_path.clone();
-        let config = std::fs::read_to_string(config_file_path);
+    /// Take the cross-process lock that every read-modify-write cycle of the
+    /// config must hold.
+    pub fn lock(&self) -> BingoResult<ConfigLock> {
+        ConfigLock::acquire(&self.paths.config_dir)
+    }
+
+    pub fn backup_file_path(&self) -> String {
+        format!("{}.bak", self.paths.config_file)
+    }
+
+    /// Where the original of a document migrated from schema `from` is kept.
+    pub fn migration_backup_path(&self, from: u32) -> String {
+        format!("{}.v{}.bak", self.paths.config_file, from)
+    }
+
+    /// Read and parse `path`, migrating older documents in memory. The
+    /// migrated config is written on the next save, and tells whether it
+    /// was migrated.
+    fn read_config(&self, path: &str) -> BingoResult<(BingoConfig, bool)> {
+        let config = std::fs::read_to_string(path);
         let config = match config {
             Err(err) => {
                 let e = BingoError::ConfigFileNotFound(err.to_string());
@@ -362,15 +1482,120 @@ impl BingoConfigFile {
             }
             Ok(s) => s,
         };
-        let config = BingoConfig::from_json(&config)?;
+        match migrate_json(&config)? {
+            Some(migrated) => {
+                let backup = self.migration_backup_path(migrated.from);
+                if !Path::new(&backup).exists() {
+                    if let Err(err) = std::fs::copy(path, &backup) {
+                        return Err(BingoError::ConfigFileError(err.to_string()));
+                    }
+                }
+                let mut config = BingoConfig::from_json(&migrated.json)?;
+                if migrated.from < 3 {
+                    config.backfill_metadata(&self.paths);
+                }
+                Ok((config, true))
+            }
+            None => Ok((BingoConfig::from_json(&config)?, false)),
+        }
+    }
+
+    /// Load the config, falling back to the previous generation kept by
+    /// `save` if the current one cannot be read.
+    pub fn load(&mut self) -> BingoResult<()> {
+        let (mut config, migrated) = match self.read_config(&self.paths.config_file) {
+            Ok(loaded) => loaded,
+            // an older backup would silently downgrade the registry
+            Err(err @ BingoError::ConfigTooNew(..)) => return Err(err),
+            Err(err) => {
+                let backup = self.backup_file_path();
+                match self.read_config(&backup) {
+                    Ok((mut config, migrated)) => {
+                        let warning = format!(
+                            "{}: {}, using backup {}",
+                            self.paths.config_file, err, backup
+                        );
+                        config.warnings.insert(0, warning);
+                        (config, migrated)
+                    }
+                    Err(_) => return Err(err),
+                }
+            }
+        };
+        if !self.quiet {
+            for warning in config.warnings.drain(..) {
+                eprintln!("{} {}", "warning:".yellow(), warning);
+            }
+        }
         self.config = config;
+        self.migrated = migrated;
         Ok(())
     }
 
+    /// Write a migrated config back right away. Otherwise commands that do
+    /// not save, like running an executable, would migrate it and backfill
+    /// metadata again on every run. Without the lock held (`locked`), it is
+    /// taken and the config reloaded first. Failing is harmless, the next
+    /// run tries again.
+    pub fn save_migrated(&mut self, locked: bool) {
+        if !self.migrated {
+            return;
+        }
+        let _lock = match locked {
+            true => None,
+            false => match self.lock() {
+                Ok(lock) => Some(lock),
+                Err(_) => return,
+            },
+        };
+        // another bingo may have saved since
+        if !locked && self.load().is_err() {
+            return;
+        }
+        if self.migrated && self.save().is_ok() {
+            self.migrated = false;
+        }
+    }
+
+    /// Run `f` against the config and save the result. If `f` or saving
+    /// fails, the filesystem changes staged in the transaction are undone
+    /// and the config is restored, so the bin dir and `bingo.json` never
+    /// disagree.
+    pub fn transaction<T, F>(&mut self, f: F) -> BingoResult<T>
+    where
+        F: FnOnce(&mut BingoConfig, &mut Transaction) -> BingoResult<T>,
+    {
+        let snapshot = self.config.clone();
+        let mut txn = Transaction::new(self.paths.clone());
+        let result = f(&mut self.config, &mut txn).and_then(|t| self.save().map(|_| t));
+        match result {
+            Ok(t) => {
+                txn.commit();
+                Ok(t)
+            }
+            Err(e) => {
+                txn.rollback();
+                self.config = snapshot;
+                Err(e)
+            }
+        }
+    }
+
+    /// Save the config atomically. The previous generation is kept as
+    /// `bingo.json.bak`.
     pub fn save(&self) -> BingoResult<()> {
-        let config_file_path = self.config_file_path.clone();
         let config = self.config.to_json()?;
-        match std::fs::write(config_file_path, config) {
+        let path = Path::new(&self.paths.config_file);
+        if path.exists() {
+            let backup = self.backup_file_path();
+            let _ = std::fs::remove_file(&backup);
+            let result = std::fs::hard_link(path, &backup)
+                .or_else(|_| std::fs::copy(path, &backup).map(|_| ()));
+            if let Err(err) = result {
+                return Err(BingoError::ConfigFileError(err.to_string()));
+            }
+        }
+        match write_atomic(path, config.as_bytes()) {
             Ok(_) => Ok(()),
             Err(err) => {
                 let e = BingoError::ConfigFileError(err.to_string());
@@ -378,9 +1603,4 @@ impl BingoConfigFile {
             }
         }
     }
-
-    pub fn export_path(&self) -> String {
-        let bin = get_bingo_bin_dir();
-        format!("export PATH={}:$PATH", bin)
-    }
 }
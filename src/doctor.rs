@@ -0,0 +1,225 @@
+use crate::config::{BingoConfig, Executable, ExecutableType, Transaction};
+use crate::error::BingoResult;
+use crate::paths::BingoPaths;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// An inconsistency between `bingo.json` and the bin dir.
+#[derive(Debug)]
+pub enum Problem {
+    /// The target of a `LinkBinary` no longer exists.
+    BrokenLink { name: String, target: String },
+    /// A copied `Binary` lost its exec bit.
+    NotExecutable { name: String },
+    /// A registered executable has no bin dir entry.
+    NotInstalled { name: String },
+    /// The bin dir entry is not what the registry says it should be.
+    WrongInstall { name: String },
+    /// An alias whose target is neither registered nor an existing file.
+    DanglingAlias { name: String, target: String },
+    /// The installed file of a version is missing or its target is gone.
+    BrokenVersion { name: String, label: String },
+    /// A file in the bin dir without a registry entry.
+    Orphan { name: String },
+    /// An entry of `bingo.json` that could not be read.
+    InvalidEntry { index: usize },
+}
+
+impl Problem {
+    pub fn class(&self) -> &'static str {
+        match self {
+            Problem::BrokenLink { .. } => "broken link",
+            Problem::NotExecutable { .. } => "not executable",
+            Problem::NotInstalled { .. } => "not installed",
+            Problem::WrongInstall { .. } => "wrong install",
+            Problem::DanglingAlias { .. } => "dangling alias",
+            Problem::BrokenVersion { .. } => "broken version",
+            Problem::Orphan { .. } => "orphan",
+            Problem::InvalidEntry { .. } => "invalid entry",
+        }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::BrokenLink { name, target } => {
+                write!(f, "{} -> {} does not exist", name, target)
+            }
+            Problem::NotExecutable { name } => write!(f, "{} has no exec bit", name),
+            Problem::NotInstalled { name } => write!(f, "{} is missing from the bin dir", name),
+            Problem::WrongInstall { name } => {
+                write!(f, "{} in the bin dir does not match the registry", name)
+            }
+            Problem::DanglingAlias { name, target } => {
+                write!(f, "{} = {}, which does not exist", name, target)
+            }
+            Problem::BrokenVersion { name, label } => {
+                write!(f, "{}@{} is missing or its target is gone", name, label)
+            }
+            Problem::Orphan { name } => write!(f, "{} is in the bin dir but not registered", name),
+            Problem::InvalidEntry { index } => {
+                write!(f, "executables[{}] in bingo.json cannot be read", index)
+            }
+        }
+    }
+}
+
+fn is_link_to(file: &str, target: &str) -> bool {
+    match std::fs::read_link(file) {
+        Ok(t) => t == Path::new(target),
+        Err(_) => false,
+    }
+}
+
+fn diagnose_executable(
+    e: &Executable,
+    config: &BingoConfig,
+    paths: &BingoPaths,
+) -> Option<Problem> {
+    let name = e.name.clone();
+    let bin = paths.entry_file(&e.name, e.disabled);
+    let meta = std::fs::symlink_metadata(&bin).ok();
+
+    if let Some(label) = &e.version {
+        for v in &e.versions {
+            let file = paths.version_file(&e.name, &v.label);
+            if !Path::new(&file).exists() {
+                return Some(Problem::BrokenVersion {
+                    name,
+                    label: v.label.clone(),
+                });
+            }
+        }
+        return match meta {
+            None => Some(Problem::NotInstalled { name }),
+            Some(_) if !is_link_to(&bin, &paths.version_file(&e.name, label)) => {
+                Some(Problem::WrongInstall { name })
+            }
+            Some(_) => None,
+        };
+    }
+
+    match e.executable_type {
+        ExecutableType::LinkBinary => {
+            if !Path::new(&e.path).exists() {
+                return Some(Problem::BrokenLink {
+                    name,
+                    target: e.path.clone(),
+                });
+            }
+            match meta {
+                None => Some(Problem::NotInstalled { name }),
+                Some(_) if !is_link_to(&bin, &e.path) => Some(Problem::WrongInstall { name }),
+                Some(_) => None,
+            }
+        }
+        ExecutableType::Binary => match meta {
+            None => Some(Problem::NotInstalled { name }),
+            Some(meta) if !meta.is_file() => Some(Problem::WrongInstall { name }),
+            Some(meta) if meta.permissions().mode() & 0o111 == 0 => {
+                Some(Problem::NotExecutable { name })
+            }
+            Some(_) => None,
+        },
+        ExecutableType::Alias => {
+            let exists = if e.path.starts_with('/') {
+                Path::new(&e.path).exists()
+            } else {
+                config.get_executable(&e.path).is_ok()
+            };
+            if !exists {
+                return Some(Problem::DanglingAlias {
+                    name,
+                    target: e.path.clone(),
+                });
+            }
+            match meta {
+                None => Some(Problem::NotInstalled { name }),
+                Some(_) => None,
+            }
+        }
+    }
+}
+
+/// Compare the registry with the bin dir.
+pub fn diagnose(config: &BingoConfig, paths: &BingoPaths) -> Vec<Problem> {
+    let mut problems = config
+        .executables
+        .iter()
+        .filter_map(|e| diagnose_executable(e, config, paths))
+        .collect::<Vec<_>>();
+
+    let orphans = config.unregistered_files(paths);
+    problems.extend(orphans.into_iter().map(|name| Problem::Orphan { name }));
+
+    // last first, so fixing one does not shift the others
+    for index in (0..config.invalid_executables.len()).rev() {
+        problems.push(Problem::InvalidEntry { index });
+    }
+    problems
+}
+
+/// Repair `problem`, returning what was done.
+pub fn fix(
+    config: &mut BingoConfig,
+    txn: &mut Transaction,
+    problem: &Problem,
+) -> BingoResult<String> {
+    match problem {
+        Problem::BrokenLink { name, .. } | Problem::DanglingAlias { name, .. } => {
+            config.remove_executable(txn, name)?;
+            Ok(format!("pruned {}", name))
+        }
+        Problem::NotExecutable { name } => {
+            let disabled = config.get_executable(name)?.disabled;
+            let bin = txn.paths.entry_file(name, disabled);
+            let result = std::fs::metadata(&bin).and_then(|meta| {
+                let mode = meta.permissions().mode() | 0o111;
+                std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(mode))
+            });
+            match result {
+                Ok(_) => Ok(format!("restored the exec bit of {}", name)),
+                Err(err) => Err(crate::error::BingoError::PermissionDenied(format!(
+                    "{}: {}",
+                    bin, err
+                ))),
+            }
+        }
+        Problem::NotInstalled { name } | Problem::WrongInstall { name } => {
+            let e = config.get_executable(name)?;
+            let source_gone = e.version.is_none()
+                && !matches!(e.executable_type, ExecutableType::Alias)
+                && !Path::new(&e.path).exists();
+            if source_gone {
+                config.remove_executable(txn, name)?;
+                return Ok(format!("pruned {}, its source is gone", name));
+            }
+            config.reinstall_executable(txn, name)?;
+            match e.executable_type {
+                ExecutableType::Binary if e.version.is_none() => Ok(format!("re-copied {}", name)),
+                _ => Ok(format!("relinked {}", name)),
+            }
+        }
+        Problem::BrokenVersion { name, label } => {
+            let e = config.get_executable(name)?;
+            let v = e.get_version(label)?;
+            if Path::new(&v.path).exists() {
+                config.reinstall_version(txn, name, label)?;
+                Ok(format!("reinstalled {}@{}", name, label))
+            } else {
+                config.remove_version(txn, name, label)?;
+                Ok(format!("pruned {}@{}", name, label))
+            }
+        }
+        Problem::Orphan { name } => {
+            let paths = txn.paths.clone();
+            let e = config.adopt_executable(&paths, name)?;
+            Ok(format!("adopted {} -> {}", name, e.path))
+        }
+        Problem::InvalidEntry { index } => {
+            config.invalid_executables.remove(*index);
+            Ok(format!("pruned executables[{}]", index))
+        }
+    }
+}
@@ -4,12 +4,31 @@ use thiserror::Error;
 pub enum BingoError {
     #[error("file not found: {0}")]
     FileNotFound(String),
+    #[error("executable not found: {0}")]
+    ExecutableNotFound(String),
     #[error("duplicate executable name: {0}")]
     DuplicateExecutableName(String),
+    #[error("invalid executable name {name:?}: {reason}")]
+    InvalidName { name: String, reason: String },
+    #[error("cannot find the bingo home, set BINGO_HOME or HOME")]
+    HomeNotFound,
     #[error("config file not found: {0}")]
     ConfigFileNotFound(String),
     #[error("config file error: {0}")]
     ConfigFileError(String),
+    #[error("invalid config at {path} (line {line}, column {column}): {message}")]
+    ConfigParseError {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("config was written by a newer bingo (schema {0}, this bingo supports up to {1})")]
+    ConfigTooNew(u32, u32),
+    #[error("unsupported config version: {0}")]
+    UnsupportedConfigVersion(String),
+    #[error("config is locked by process {0} (lock file: {1})")]
+    ConfigLocked(String, String),
     #[error("executable must be a file: {0}")]
     ExecutableNotFile(String),
     #[error("executable cannot be executed: {0}")]
@@ -20,6 +39,52 @@ pub enum BingoError {
     CopyError(String),
     #[error("Link error: {0}")]
     LinkError(String),
+    #[error("file system error: {0}")]
+    FileSystemError(String),
+    #[error("Alias error: {0}")]
+    AliasError(String),
+    #[error("alias cycle: {0}")]
+    AliasCycle(String),
+    #[error("version not found: {0}")]
+    VersionNotFound(String),
+    #[error("duplicate version: {0}")]
+    DuplicateVersion(String),
+    #[error("cannot remove the active version: {0}")]
+    ActiveVersion(String),
+    #[error("invalid version label: {0}")]
+    InvalidVersion(String),
+    #[error("not in the bin dir: {0}")]
+    NotInBinDir(String),
+    #[error("unknown setting: {0}")]
+    UnknownSetting(String),
+    #[error("invalid setting: {0}")]
+    InvalidSetting(String),
+    #[error("checksum mismatch for {name}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error("invalid tag: {0}")]
+    InvalidTag(String),
+    #[error("no executables tagged {0}")]
+    TagNotFound(String),
+    #[error("executable is disabled: {0}, enable it or use `bingo run --force`")]
+    ExecutableDisabled(String),
+    #[error("{name} has {count} matches in PATH, not {nth}")]
+    NoSuchPathMatch {
+        name: String,
+        nth: usize,
+        count: usize,
+    },
+    #[error("--name takes a single file, got {0}")]
+    NameNeedsSingleFile(usize),
+    #[error("invalid environment variable, expected KEY=VALUE: {0}")]
+    InvalidEnvVar(String),
+    #[error("file name is not valid UTF-8: {0}")]
+    NonUtf8FileName(String),
 }
 
 pub type BingoResult<T> = Result<T, BingoError>;
@@ -1,3 +1,16 @@
+pub mod add;
 pub mod cli;
+pub mod complete;
 pub mod config;
+pub mod doctor;
 pub mod error;
+pub mod list;
+pub mod lock;
+pub mod metadata;
+pub mod naming;
+pub mod paths;
+pub mod process;
+pub mod search;
+pub mod shell;
+pub mod update;
+pub mod verify;
@@ -0,0 +1,338 @@
+use crate::config::{BingoConfig, Executable, ExecutableType};
+use crate::metadata::format_time;
+use crate::paths::BingoPaths;
+use colored::*;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Plain,
+    Table,
+    Json,
+}
+
+impl From<&str> for Format {
+    fn from(s: &str) -> Self {
+        match s {
+            "table" => Format::Table,
+            "json" => Format::Json,
+            _ => Format::Plain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SortKey {
+    Name,
+    /// Install time, oldest first.
+    Added,
+    /// Installed size, largest first.
+    Size,
+}
+
+impl From<&str> for SortKey {
+    fn from(s: &str) -> Self {
+        match s {
+            "added" => SortKey::Added,
+            "size" => SortKey::Size,
+            _ => SortKey::Name,
+        }
+    }
+}
+
+/// Whether what `e` runs is still there: the installed copy, the link
+/// target or the alias target.
+pub fn target_exists(config: &BingoConfig, paths: &BingoPaths, e: &Executable) -> bool {
+    match e.executable_type {
+        ExecutableType::Binary if e.version.is_none() => {
+            Path::new(&paths.entry_file(&e.name, e.disabled)).exists()
+        }
+        ExecutableType::Alias if !e.path.starts_with('/') => config.get_executable(&e.path).is_ok(),
+        _ => Path::new(&e.path).exists(),
+    }
+}
+
+/// Which executables `ls` shows. Empty criteria match everything.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// `copy`, `link` or `alias`, see `ExecutableType::kind`.
+    pub kind: Option<String>,
+    /// Only entries whose target is gone.
+    pub broken: bool,
+    /// Entries with any of these tags.
+    pub tags: Vec<String>,
+    /// Entries whose name matches any of these globs.
+    pub patterns: Vec<glob::Pattern>,
+}
+
+impl Filter {
+    pub fn matches(&self, config: &BingoConfig, paths: &BingoPaths, e: &Executable) -> bool {
+        if self
+            .kind
+            .as_deref()
+            .is_some_and(|kind| kind != e.executable_type.kind())
+        {
+            return false;
+        }
+        if !self.tags.is_empty() && !e.tags.iter().any(|t| self.tags.contains(t)) {
+            return false;
+        }
+        if !self.patterns.is_empty() && !self.patterns.iter().any(|p| p.matches(&e.name)) {
+            return false;
+        }
+        !self.broken || !target_exists(config, paths, e)
+    }
+}
+
+pub fn sort(executables: &mut [&Executable], key: SortKey) {
+    match key {
+        SortKey::Name => executables.sort_by(|a, b| a.name.cmp(&b.name)),
+        SortKey::Added => executables.sort_by_key(|e| e.metadata.installed_at),
+        SortKey::Size => executables.sort_by_key(|e| std::cmp::Reverse(e.metadata.size)),
+    }
+}
+
+fn display_name(e: &Executable) -> String {
+    match &e.version {
+        Some(label) => format!("{}@{}", e.name, label),
+        None => e.name.clone(),
+    }
+}
+
+fn status(config: &BingoConfig, paths: &BingoPaths, e: &Executable) -> &'static str {
+    match target_exists(config, paths, e) {
+        false => "missing",
+        true if e.disabled => "disabled",
+        true => "ok",
+    }
+}
+
+fn target(e: &Executable) -> String {
+    let mut target = vec![e.path.as_str()];
+    target.extend(e.args.iter().map(|a| a.as_str()));
+    target.join(" ")
+}
+
+fn size(e: &Executable) -> String {
+    match e.metadata.size {
+        Some(size) => size.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn installed_at(e: &Executable) -> String {
+    match e.metadata.installed_at {
+        Some(secs) => format_time(secs),
+        None => "-".to_string(),
+    }
+}
+
+/// The numbered listing, with the details of `-l` after each entry.
+pub fn print_plain(
+    config: &BingoConfig,
+    paths: &BingoPaths,
+    executables: &[&Executable],
+    long: bool,
+) {
+    for (index, e) in executables.iter().enumerate() {
+        let index = index + 1;
+        let name = display_name(e);
+        let line = match e.executable_type {
+            ExecutableType::Binary => format!("{index}: {} => {}", name, e.path.green()),
+            ExecutableType::LinkBinary => format!("{index}: {} -> {}", name, e.path.cyan()),
+            ExecutableType::Alias => format!("{index}: {} = {}", name, target(e).magenta()),
+        };
+        match e.disabled {
+            true => println!("{} {}", line, "(disabled)".yellow()),
+            false => println!("{}", line),
+        }
+        if long {
+            let status = match status(config, paths, e) {
+                "ok" => "ok".green(),
+                "disabled" => "disabled".yellow(),
+                status => status.red(),
+            };
+            let mut details = vec![e.executable_type.kind().to_string(), status.to_string()];
+            if let Some(size) = e.metadata.size {
+                details.push(format!("{} bytes", size));
+            }
+            details.push(format!("installed {}", installed_at(e)));
+            println!("   {}", details.join(", "));
+        }
+    }
+}
+
+/// Aligned columns with a header, the long view adds status, size and
+/// install time.
+pub fn print_table(
+    config: &BingoConfig,
+    paths: &BingoPaths,
+    executables: &[&Executable],
+    long: bool,
+) {
+    let mut header = vec!["NAME", "TYPE", "TARGET"];
+    if long {
+        header.extend(["STATUS", "SIZE", "INSTALLED"]);
+    }
+    let rows = executables
+        .iter()
+        .map(|e| {
+            let mut row = vec![
+                display_name(e),
+                e.executable_type.kind().to_string(),
+                target(e),
+            ];
+            if long {
+                let status = status(config, paths, e).to_string();
+                row.extend([status, size(e), installed_at(e)]);
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| {
+        let last = cells.len() - 1;
+        let cells = cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, cell)| match i == last {
+                true => cell,
+                false => format!("{:<width$}", cell, width = widths[i]),
+            })
+            .collect::<Vec<_>>();
+        cells.join("  ")
+    };
+    println!(
+        "{}",
+        line(header.iter().map(|h| h.to_string()).collect()).bold()
+    );
+    for row in rows {
+        println!("{}", line(row));
+    }
+}
+
+/// One object per executable, for scripts. Always includes the long view.
+pub fn print_json(config: &BingoConfig, paths: &BingoPaths, executables: &[&Executable]) {
+    let entries = executables
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "name": e.name,
+                "version": e.version,
+                "type": e.executable_type.kind(),
+                "path": e.path,
+                "args": e.args,
+                "exists": target_exists(config, paths, e),
+                "disabled": e.disabled,
+                "tags": e.tags,
+                "size": e.metadata.size,
+                "installed_at": e.metadata.installed_at,
+                "sha256": e.metadata.sha256,
+                "description": e.metadata.description,
+            })
+        })
+        .collect::<Vec<_>>();
+    // serializing a `Value` cannot fail
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+}
+
+pub fn print(
+    config: &BingoConfig,
+    paths: &BingoPaths,
+    executables: &[&Executable],
+    format: Format,
+    long: bool,
+) {
+    match format {
+        Format::Json => print_json(config, paths, executables),
+        _ if executables.is_empty() => println!("No executables found."),
+        Format::Table => print_table(config, paths, executables, long),
+        Format::Plain => print_plain(config, paths, executables, long),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filtered<'a>(filter: &Filter, config: &'a BingoConfig) -> Vec<&'a str> {
+        let paths = BingoPaths::from_home("/nonexistent/bingo");
+        config
+            .executables
+            .iter()
+            .filter(|e| filter.matches(config, &paths, e))
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn config() -> BingoConfig {
+        let mut config = BingoConfig::new();
+        let mut samtools = Executable::new("samtools", "/bin/sh", ExecutableType::LinkBinary);
+        samtools.tags = vec!["bio".to_string()];
+        let mut bcftools = Executable::new(
+            "bcftools",
+            "/nonexistent/bcftools",
+            ExecutableType::LinkBinary,
+        );
+        bcftools.tags = vec!["bio".to_string()];
+        let kubectl = Executable::new("kubectl", "/bin/sh", ExecutableType::LinkBinary);
+        let k = Executable::new("k", "kubectl", ExecutableType::Alias);
+        config.executables = vec![samtools, bcftools, kubectl, k];
+        config
+    }
+
+    #[test]
+    fn an_empty_filter_matches_everything() {
+        let config = config();
+        let all = filtered(&Filter::default(), &config);
+        assert_eq!(all, ["samtools", "bcftools", "kubectl", "k"]);
+    }
+
+    #[test]
+    fn single_criteria() {
+        let config = config();
+        let kind = Filter {
+            kind: Some("alias".to_string()),
+            ..Filter::default()
+        };
+        assert_eq!(filtered(&kind, &config), ["k"]);
+        let broken = Filter {
+            broken: true,
+            ..Filter::default()
+        };
+        assert_eq!(filtered(&broken, &config), ["bcftools"]);
+        let pattern = Filter {
+            patterns: vec![glob::Pattern::new("*tools").unwrap()],
+            ..Filter::default()
+        };
+        assert_eq!(filtered(&pattern, &config), ["samtools", "bcftools"]);
+    }
+
+    #[test]
+    fn combined_criteria_all_apply() {
+        let config = config();
+        let filter = Filter {
+            kind: Some("link".to_string()),
+            broken: true,
+            tags: vec!["bio".to_string()],
+            patterns: vec![glob::Pattern::new("*tools").unwrap()],
+        };
+        assert_eq!(filtered(&filter, &config), ["bcftools"]);
+        let filter = Filter {
+            tags: vec!["bio".to_string(), "k8s".to_string()],
+            patterns: vec![glob::Pattern::new("s*").unwrap()],
+            ..Filter::default()
+        };
+        assert_eq!(filtered(&filter, &config), ["samtools"]);
+        let filter = Filter {
+            tags: vec!["k8s".to_string()],
+            ..Filter::default()
+        };
+        assert!(filtered(&filter, &config).is_empty());
+    }
+}
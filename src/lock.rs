@@ -0,0 +1,83 @@
+use crate::error::{BingoError, BingoResult};
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
+
+static LOCK_FILE: &str = "bingo.lock";
+static LOCK_TIMEOUT_ENV: &str = "BINGO_LOCK_TIMEOUT";
+static DEFAULT_LOCK_TIMEOUT: u64 = 10;
+
+/// Advisory lock on the config dir, held for a whole load → modify → save
+/// cycle. Released when dropped.
+#[derive(Debug)]
+pub struct ConfigLock {
+    file: File,
+}
+
+fn lock_timeout() -> Duration {
+    let secs = std::env::var(LOCK_TIMEOUT_ENV)
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT);
+    Duration::from_secs(secs)
+}
+
+impl ConfigLock {
+    /// Wait for the lock in `config_dir` for up to `BINGO_LOCK_TIMEOUT`
+    /// seconds (10 by default).
+    pub fn acquire(config_dir: &str) -> BingoResult<ConfigLock> {
+        let path = format!("{}/{}", config_dir, LOCK_FILE);
+        let mut file = match std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(err) => return Err(BingoError::ConfigFileError(err.to_string())),
+        };
+
+        let deadline = Instant::now() + lock_timeout();
+        loop {
+            let ret = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+            if ret == 0 {
+                break;
+            }
+            let err = std::io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::EWOULDBLOCK) {
+                return Err(BingoError::ConfigFileError(err.to_string()));
+            }
+            if Instant::now() >= deadline {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = match holder.trim() {
+                    "" => "unknown".to_string(),
+                    pid => pid.to_string(),
+                };
+                return Err(BingoError::ConfigLocked(holder, path));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        // record the holder for whoever has to wait for us
+        let result = file
+            .set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| write!(file, "{}", std::process::id()))
+            .and_then(|_| file.flush());
+        if let Err(err) = result {
+            return Err(BingoError::ConfigFileError(err.to_string()));
+        }
+        Ok(ConfigLock { file })
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        // clear the pid while still holding the lock, closing the file
+        // releases it
+        let _ = self.file.set_len(0);
+    }
+}
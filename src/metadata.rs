@@ -0,0 +1,103 @@
+use crate::error::{BingoError, BingoResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What was installed and when, for auditing. Times are seconds since the
+/// epoch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Metadata {
+    /// SHA-256 of the installed content, the link target for `LinkBinary`.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Modification time of the source when it was installed.
+    #[serde(default)]
+    pub source_mtime: Option<u64>,
+    /// Nanoseconds of `source_mtime`, so a rebuild within the same second
+    /// still counts as a change.
+    #[serde(default)]
+    pub source_mtime_nanos: Option<u32>,
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+    /// Version of bingo that installed it, unknown for entries written
+    /// before metadata was recorded.
+    #[serde(default)]
+    pub installed_by: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn seconds(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// Modification time of `path` as seconds and nanoseconds since the epoch.
+pub fn mtime(meta: &std::fs::Metadata) -> Option<(u64, u32)> {
+    let since = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((since.as_secs(), since.subsec_nanos()))
+}
+
+/// Format seconds since the epoch as an RFC 3339 timestamp in UTC.
+pub fn format_time(secs: u64) -> String {
+    let time = UNIX_EPOCH + Duration::from_secs(secs);
+    humantime::format_rfc3339_seconds(time).to_string()
+}
+
+/// Hex encoded SHA-256 of the file at `path`, following symlinks.
+pub fn sha256_file(path: &str) -> BingoResult<String> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) => return Err(BingoError::FileSystemError(format!("{}: {}", path, err))),
+    };
+    let mut hasher = Sha256::new();
+    if let Err(err) = std::io::copy(&mut file, &mut hasher) {
+        return Err(BingoError::FileSystemError(format!("{}: {}", path, err)));
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+impl Metadata {
+    /// Metadata of something installed just now by this bingo.
+    pub fn installed_now() -> Metadata {
+        Metadata {
+            installed_at: seconds(SystemTime::now()),
+            installed_by: Some(env!("CARGO_PKG_VERSION").to_string()),
+            ..Metadata::default()
+        }
+    }
+
+    /// Record the content of `installed` and the mtime of `source`.
+    pub fn collect(source: &str, installed: &str) -> BingoResult<Metadata> {
+        let mut metadata = Metadata::installed_now();
+        metadata.record_content(source, installed)?;
+        Ok(metadata)
+    }
+
+    /// Fill in what can still be found out about an install that predates
+    /// metadata. The install time is taken from the bin dir entry, which is
+    /// created by the install.
+    pub fn backfill(source: &str, installed: &str) -> BingoResult<Metadata> {
+        let mut metadata = Metadata::default();
+        metadata.record_content(source, installed)?;
+        metadata.installed_at = std::fs::symlink_metadata(installed)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(seconds);
+        Ok(metadata)
+    }
+
+    fn record_content(&mut self, source: &str, installed: &str) -> BingoResult<()> {
+        self.sha256 = Some(sha256_file(installed)?);
+        self.size = std::fs::metadata(installed).ok().map(|m| m.len());
+        self.record_source_mtime(source);
+        Ok(())
+    }
+
+    pub fn record_source_mtime(&mut self, source: &str) {
+        let mtime = std::fs::metadata(source).ok().and_then(|m| mtime(&m));
+        self.source_mtime = mtime.map(|(secs, _)| secs);
+        self.source_mtime_nanos = mtime.map(|(_, nanos)| nanos);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::error::{BingoError, BingoResult};
+
+/// One step of deriving an executable's name from its file name. Steps
+/// are applied in the order given, e.g. `ext,version,lower`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameRule {
+    /// Cut at the first `.`, `foo.tar.sh` becomes `foo`.
+    Stem,
+    /// Keep the file name as it is.
+    Full,
+    /// Strip one known extension, `foo.tar.sh` becomes `foo.tar`.
+    Ext,
+    /// Strip a version suffix, `node-v20.1.0` becomes `node` and
+    /// `python3.11` becomes `python3`.
+    Version,
+    Lower,
+}
+
+impl NameRule {
+    pub const NAMES: &'static [&'static str] = &["stem", "full", "ext", "version", "lower"];
+    /// What bingo always did, used unless `name-rule` is set.
+    pub const DEFAULT: &'static str = "stem";
+
+    fn apply(self, name: &str) -> String {
+        match self {
+            NameRule::Stem => name.split('.').next().unwrap().to_string(),
+            NameRule::Full => name.to_string(),
+            NameRule::Ext => strip_extension(name).to_string(),
+            NameRule::Version => strip_version(name).to_string(),
+            NameRule::Lower => name.to_lowercase(),
+        }
+    }
+}
+
+impl From<&str> for NameRule {
+    fn from(s: &str) -> Self {
+        match s {
+            "full" => NameRule::Full,
+            "ext" => NameRule::Ext,
+            "version" => NameRule::Version,
+            "lower" => NameRule::Lower,
+            _ => NameRule::Stem,
+        }
+    }
+}
+
+/// Extensions of scripts and executables that are no part of the command.
+const EXTENSIONS: &[&str] = &[
+    "sh", "bash", "zsh", "fish", "py", "pl", "rb", "js", "mjs", "ts", "lua", "php", "r", "jl",
+    "exe", "bat", "cmd", "ps1", "bin", "run", "appimage",
+];
+
+fn strip_extension(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, ext))
+            if !stem.is_empty() && EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)) =>
+        {
+            stem
+        }
+        _ => name,
+    }
+}
+
+/// Strip trailing digits and dots led by `-`, `_`, `-v` or `_v`, or else
+/// everything from their first dot on.
+fn strip_version(name: &str) -> &str {
+    let start = name
+        .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .len();
+    // `1.2.3` is all version, there is no command to keep
+    if start == 0 || !name[start..].contains(|c: char| c.is_ascii_digit()) {
+        return name;
+    }
+    let head = &name[..start];
+    let head = match head.strip_suffix('v') {
+        Some(h) if h.ends_with(['-', '_']) => h,
+        _ => head,
+    };
+    let cut = if head.ends_with(['-', '_']) {
+        head.len() - 1
+    } else {
+        match name[start..].find('.') {
+            Some(i) => start + i,
+            None => return name,
+        }
+    };
+    if cut == 0 {
+        return name;
+    }
+    &name[..cut]
+}
+
+/// Parse a comma separated list of rules, as in `--name-rule ext,lower`.
+pub fn parse_rules(s: &str) -> Option<Vec<NameRule>> {
+    s.split(',')
+        .map(str::trim)
+        .map(|rule| {
+            NameRule::NAMES
+                .contains(&rule)
+                .then(|| NameRule::from(rule))
+        })
+        .collect()
+}
+
+/// The name for an executable file called `file_name`.
+pub fn derive_name(file_name: &str, rules: &[NameRule]) -> String {
+    rules
+        .iter()
+        .fold(file_name.to_string(), |name, rule| rule.apply(&name))
+}
+
+/// Characters that need quoting in a shell, besides whitespace.
+const UNSAFE: &str = "/\\\"'`$;&|<>(){}[]*?!#~";
+
+/// Check that `name` works as a file name in the bin dir and as a command
+/// typed into a shell.
+pub fn check_name(name: &str) -> BingoResult<()> {
+    let reason = if name.is_empty() {
+        "it is empty".to_string()
+    } else if name.starts_with('-') || name.starts_with('.') {
+        "it starts with '-' or '.'".to_string()
+    } else if let Some(c) = name
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || UNSAFE.contains(*c))
+    {
+        format!("it contains {:?}", c)
+    } else {
+        return Ok(());
+    };
+    Err(BingoError::InvalidName {
+        name: name.to_string(),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derived(file_name: &str, rules: &str) -> String {
+        derive_name(file_name, &parse_rules(rules).unwrap())
+    }
+
+    #[test]
+    fn version_suffixes() {
+        assert_eq!(strip_version("python3"), "python3");
+        assert_eq!(strip_version("python3.11"), "python3");
+        assert_eq!(strip_version("node-v20.1.0"), "node");
+        assert_eq!(strip_version("node_v20"), "node");
+        assert_eq!(strip_version("tool-2"), "tool");
+        assert_eq!(strip_version("tool2"), "tool2");
+        assert_eq!(strip_version("1.2.3"), "1.2.3");
+        assert_eq!(strip_version("-1.2"), "-1.2");
+        assert_eq!(strip_version("tool.sh"), "tool.sh");
+    }
+
+    #[test]
+    fn known_extensions() {
+        assert_eq!(strip_extension("build.sh"), "build");
+        assert_eq!(strip_extension("Build.SH"), "Build");
+        assert_eq!(strip_extension("foo.tar.sh"), "foo.tar");
+        assert_eq!(strip_extension("notes.txt"), "notes.txt");
+        assert_eq!(strip_extension(".sh"), ".sh");
+        assert_eq!(strip_extension("sh"), "sh");
+    }
+
+    #[test]
+    fn each_rule() {
+        assert_eq!(derived("foo.tar.sh", "stem"), "foo");
+        assert_eq!(derived("foo.tar.sh", "full"), "foo.tar.sh");
+        assert_eq!(derived("foo.tar.sh", "ext"), "foo.tar");
+        assert_eq!(derived("python3.11", "version"), "python3");
+        assert_eq!(derived("Build.SH", "lower"), "build.sh");
+        assert_eq!(derived("python3.11", NameRule::DEFAULT), "python3");
+        assert_eq!(derived(".sh", "stem"), "");
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        assert_eq!(derived("tool.1.sh", "ext"), "tool.1");
+        assert_eq!(derived("tool.1.sh", "stem,ext"), "tool");
+        assert_eq!(derived("python3.11.py", "ext,version"), "python3");
+        assert_eq!(derived("python3.11.py", "version,ext"), "python3.11");
+        assert_eq!(derived("Build.SH", "ext,lower"), "build");
+        assert_eq!(derived("Build.SH", "lower,full"), "build.sh");
+    }
+
+    #[test]
+    fn rule_lists() {
+        assert_eq!(
+            parse_rules("ext, version,lower"),
+            Some(vec![NameRule::Ext, NameRule::Version, NameRule::Lower])
+        );
+        assert_eq!(parse_rules("stem"), Some(vec![NameRule::Stem]));
+        assert_eq!(parse_rules("ext,bogus"), None);
+        assert_eq!(parse_rules("ext,"), None);
+        assert_eq!(parse_rules(""), None);
+    }
+
+    fn reason(name: &str) -> String {
+        match check_name(name) {
+            Err(BingoError::InvalidName { reason, .. }) => reason,
+            other => panic!("{:?} was not refused: {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn valid_names() {
+        for name in ["ls", "python3", "my-tool_2", "g++", "node.js"] {
+            assert!(check_name(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn refused_names() {
+        assert_eq!(reason(""), "it is empty");
+        assert_eq!(reason("-x"), "it starts with '-' or '.'");
+        assert_eq!(reason(".sh"), "it starts with '-' or '.'");
+        assert_eq!(reason("my tool"), "it contains ' '");
+        assert_eq!(reason("tab\tbed"), "it contains '\\t'");
+        assert_eq!(reason("bell\u{7}"), "it contains '\\u{7}'");
+        assert_eq!(reason("a/b"), "it contains '/'");
+        assert_eq!(reason("$HOME"), "it contains '$'");
+        assert_eq!(reason("a;b"), "it contains ';'");
+        assert_eq!(reason("*"), "it contains '*'");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::{BingoError, BingoResult};
+use std::path::{Path, PathBuf};
+
+static CONFIG_DIR: &str = ".bingo";
+static CONFIG_FILE: &str = "bingo.json";
+static HOME_ENV: &str = "BINGO_HOME";
+
+/// Where bingo keeps its registry and installed executables.
+#[derive(Debug, Clone)]
+pub struct BingoPaths {
+    /// Holds `bingo.json`, its backups and the lock file.
+    pub config_dir: String,
+    pub config_file: String,
+    /// The directory users put on `$PATH`.
+    pub bin_dir: String,
+    /// Installed versions live in `versions/<name>/<label>`, the bin dir
+    /// entry of a versioned executable is a symlink to the active one.
+    pub versions_dir: String,
+    /// Bin dir entries of disabled executables wait here.
+    pub disabled_dir: String,
+    /// The single directory holding everything, unless the XDG layout is
+    /// used. Passed as `--home` by generated wrappers.
+    pub home: Option<String>,
+}
+
+fn non_empty_var(key: &str) -> Option<String> {
+    match std::env::var(key) {
+        Ok(v) if !v.is_empty() => Some(v),
+        _ => None,
+    }
+}
+
+/// `path` relative to the current directory, without `.` components.
+pub fn absolute(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(cwd) if path.is_relative() => cwd.join(path),
+        _ => path.to_path_buf(),
+    };
+    path.components().collect()
+}
+
+impl BingoPaths {
+    /// Everything in one directory, the layout of `$HOME/.bingo`. A relative
+    /// `home` is made absolute, it ends up in wrappers run from anywhere.
+    pub fn from_home(home: &str) -> BingoPaths {
+        let home = absolute(Path::new(home));
+        let home = home.to_str().unwrap().trim_end_matches('/');
+        BingoPaths {
+            config_dir: home.to_string(),
+            config_file: format!("{}/{}", home, CONFIG_FILE),
+            bin_dir: format!("{}/bin", home),
+            versions_dir: format!("{}/versions", home),
+            disabled_dir: format!("{}/disabled", home),
+            home: Some(home.to_string()),
+        }
+    }
+
+    /// Resolve the bingo home, in order of precedence: `home` (the `--home`
+    /// flag), `$BINGO_HOME`, the XDG base directories if `XDG_CONFIG_HOME`
+    /// or `XDG_DATA_HOME` is set and `$HOME/.bingo` does not exist yet, and
+    /// finally `$HOME/.bingo`.
+    pub fn resolve(home: Option<&str>) -> BingoResult<BingoPaths> {
+        if let Some(home) = home {
+            return Ok(BingoPaths::from_home(home));
+        }
+        if let Some(home) = non_empty_var(HOME_ENV) {
+            return Ok(BingoPaths::from_home(&home));
+        }
+        let user_home = non_empty_var("HOME");
+        let legacy = user_home
+            .as_ref()
+            .map(|home| format!("{}/{}", home, CONFIG_DIR));
+        let xdg_config = non_empty_var("XDG_CONFIG_HOME");
+        let xdg_data = non_empty_var("XDG_DATA_HOME");
+        let legacy_exists = legacy.as_ref().is_some_and(|l| Path::new(l).exists());
+        if (xdg_config.is_some() || xdg_data.is_some()) && !legacy_exists {
+            let config_home =
+                xdg_config.or_else(|| user_home.as_ref().map(|h| h.clone() + "/.config"));
+            let data_home =
+                xdg_data.or_else(|| user_home.as_ref().map(|h| h.clone() + "/.local/share"));
+            if let (Some(config_home), Some(data_home)) = (config_home, data_home) {
+                let config_dir = format!("{}/bingo", config_home);
+                let data_dir = format!("{}/bingo", data_home);
+                return Ok(BingoPaths {
+                    config_file: format!("{}/{}", config_dir, CONFIG_FILE),
+                    config_dir,
+                    bin_dir: format!("{}/bin", data_dir),
+                    versions_dir: format!("{}/versions", data_dir),
+                    disabled_dir: format!("{}/disabled", data_dir),
+                    home: None,
+                });
+            }
+        }
+        match legacy {
+            Some(legacy) => Ok(BingoPaths::from_home(&legacy)),
+            None => Err(BingoError::HomeNotFound),
+        }
+    }
+
+    /// The bin dir entry of `name`.
+    pub fn bin_file(&self, name: &str) -> String {
+        format!("{}/{}", self.bin_dir, name)
+    }
+
+    /// Where the bin dir entry of `name` is kept while it is disabled.
+    pub fn disabled_file(&self, name: &str) -> String {
+        format!("{}/{}", self.disabled_dir, name)
+    }
+
+    /// The bin dir entry of `name`, or where it waits while disabled.
+    pub fn entry_file(&self, name: &str, disabled: bool) -> String {
+        match disabled {
+            true => self.disabled_file(name),
+            false => self.bin_file(name),
+        }
+    }
+
+    pub fn version_dir(&self, name: &str) -> String {
+        format!("{}/{}", self.versions_dir, name)
+    }
+
+    pub fn version_file(&self, name: &str, label: &str) -> String {
+        format!("{}/{}/{}", self.versions_dir, name, label)
+    }
+}
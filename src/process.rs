@@ -0,0 +1,118 @@
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// Pid of the running child, read by the signal handler.
+static CHILD_PID: AtomicI32 = AtomicI32::new(0);
+
+static FORWARDED_SIGNALS: &[libc::c_int] = &[libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+/// Signals generated by the terminal already reach the child through the
+/// foreground process group, only the ones another process sent to bingo
+/// itself, with kill(2), sigqueue(3) or tgkill(2), have to be passed on.
+/// Those have a `si_code` of zero or below, the kernel's are positive.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn sent_to_us(info: *const libc::siginfo_t) -> bool {
+    info.is_null() || (*info).si_code <= 0
+}
+
+/// `SI_USER` and `SI_QUEUE`, sent by kill(2) and sigqueue(3).
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+unsafe fn sent_to_us(info: *const libc::siginfo_t) -> bool {
+    info.is_null() || matches!((*info).si_code, 0x10001 | 0x10002)
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+unsafe fn sent_to_us(_info: *const libc::siginfo_t) -> bool {
+    true
+}
+
+extern "C" fn forward_signal(
+    signo: libc::c_int,
+    info: *mut libc::siginfo_t,
+    _ctx: *mut libc::c_void,
+) {
+    let pid = CHILD_PID.load(Ordering::SeqCst);
+    unsafe {
+        if pid > 0 && sent_to_us(info) {
+            libc::kill(pid, signo);
+        }
+    }
+}
+
+/// Signals ignored when bingo starts, like SIGHUP under `nohup`, are left
+/// alone so the child inherits them ignored.
+fn install_forwarders() {
+    for signo in FORWARDED_SIGNALS {
+        unsafe {
+            let mut old: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(*signo, std::ptr::null(), &mut old);
+            if old.sa_sigaction == libc::SIG_IGN {
+                continue;
+            }
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = forward_signal as *const () as libc::sighandler_t;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(*signo, &action, std::ptr::null_mut());
+        }
+    }
+}
+
+/// Block the forwarded signals for the calling thread, returning the mask
+/// to restore.
+fn block_forwarded() -> libc::sigset_t {
+    unsafe {
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        let mut old: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        for signo in FORWARDED_SIGNALS {
+            libc::sigaddset(&mut set, *signo);
+        }
+        libc::pthread_sigmask(libc::SIG_BLOCK, &set, &mut old);
+        old
+    }
+}
+
+/// Convert a child's exit status into the code bingo should exit with,
+/// following the shell convention of `128 + signo` for fatal signals.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    match status.code() {
+        Some(code) => code,
+        None => match status.signal() {
+            Some(signo) => 128 + signo,
+            None => 1,
+        },
+    }
+}
+
+/// Spawn `command`, forward SIGINT/SIGTERM/SIGHUP to it while it runs and
+/// return the code bingo should exit with.
+pub fn spawn_and_wait(command: &mut Command) -> std::io::Result<i32> {
+    // handlers that catch a signal are reset by exec, so the child starts
+    // with the default dispositions
+    install_forwarders();
+    // a signal arriving before the pid is known stays pending until then,
+    // the child gets an empty mask from `spawn`
+    let mask = block_forwarded();
+    let child = command.spawn();
+    if let Ok(child) = &child {
+        CHILD_PID.store(child.id() as i32, Ordering::SeqCst);
+    }
+    unsafe {
+        libc::pthread_sigmask(libc::SIG_SETMASK, &mask, std::ptr::null_mut());
+    }
+    let status = child?.wait();
+    CHILD_PID.store(0, Ordering::SeqCst);
+    Ok(exit_code(status?))
+}
+
+/// Replace the bingo process with `command`. Only returns if the exec failed.
+pub fn exec(command: &mut Command) -> std::io::Error {
+    command.exec()
+}
@@ -0,0 +1,102 @@
+use crate::config::Executable;
+
+/// Score `text` against `query` as a case-insensitive subsequence, higher
+/// is better. Consecutive characters and matches at the start of a word
+/// count more, gaps cost a little, and matching all of `text` counts most.
+/// `None` if not every character of the query appears in order.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text = text.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+    for q in query.chars().flat_map(|c| c.to_lowercase()) {
+        let found = (pos..text.len()).find(|&i| text[i].to_lowercase().eq(std::iter::once(q)))?;
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if let Some(last) = last_match {
+            score -= (found - last - 1).min(5) as i64;
+        }
+        last_match = Some(found);
+        pos = found + 1;
+    }
+    // every character of `text` was matched
+    if pos == text.len() && query.chars().count() == text.len() {
+        score += 3;
+    }
+    Some(score)
+}
+
+/// Best score of `e` over its name, description, tags and path. Matches
+/// in the name are worth the most.
+pub fn score(query: &str, e: &Executable) -> Option<i64> {
+    let mut fields = vec![(e.name.as_str(), 3)];
+    if let Some(description) = &e.metadata.description {
+        fields.push((description, 2));
+    }
+    fields.extend(e.tags.iter().map(|t| (t.as_str(), 2)));
+    fields.push((e.path.as_str(), 1));
+    fields
+        .into_iter()
+        .filter_map(|(text, weight)| fuzzy_score(query, text).map(|s| s * weight))
+        .max()
+}
+
+/// Executables matching `query`, best first.
+pub fn search<'a>(query: &str, executables: &'a [Executable]) -> Vec<&'a Executable> {
+    let mut found = executables
+        .iter()
+        .filter_map(|e| score(query, e).map(|s| (s, e)))
+        .collect::<Vec<_>>();
+    found.sort_by(|(a, ea), (b, eb)| b.cmp(a).then_with(|| ea.name.cmp(&eb.name)));
+    found.into_iter().map(|(_, e)| e).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExecutableType;
+
+    #[test]
+    fn exact_beats_prefix_beats_subsequence() {
+        let exact = fuzzy_score("ls", "ls").unwrap();
+        let prefix = fuzzy_score("ls", "lsof").unwrap();
+        let subsequence = fuzzy_score("ls", "glass").unwrap();
+        assert!(exact > prefix, "{} > {}", exact, prefix);
+        assert!(prefix > subsequence, "{} > {}", prefix, subsequence);
+    }
+
+    #[test]
+    fn word_starts_and_runs_count_more() {
+        assert!(fuzzy_score("b", "a-b") > fuzzy_score("b", "ab"));
+        assert!(fuzzy_score("ab", "abx") > fuzzy_score("ab", "axb"));
+        assert_eq!(fuzzy_score("LS", "ls"), fuzzy_score("ls", "ls"));
+    }
+
+    #[test]
+    fn no_match() {
+        assert_eq!(fuzzy_score("sl", "ls"), None);
+        assert_eq!(fuzzy_score("lsx", "ls"), None);
+    }
+
+    #[test]
+    fn search_ranks_names_first() {
+        let mut described = Executable::new("tool", "/opt/tool", ExecutableType::Binary);
+        described.metadata.description = Some("samtools wrapper".to_string());
+        let executables = vec![
+            Executable::new("glass", "/opt/glass", ExecutableType::Binary),
+            described,
+            Executable::new("samtools", "/opt/samtools", ExecutableType::Binary),
+            Executable::new("other", "/opt/other", ExecutableType::Binary),
+        ];
+        let names = search("samtools", &executables)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["samtools", "tool"]);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::config::shell_quote;
+use crate::paths::BingoPaths;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nu,
+    /// PowerShell on Linux or macOS, where `PATH` is separated by `:`.
+    PowerShell,
+}
+
+impl Shell {
+    pub const NAMES: &'static [&'static str] = &["bash", "zsh", "fish", "nu", "powershell"];
+}
+
+impl From<&str> for Shell {
+    fn from(s: &str) -> Self {
+        match s {
+            "zsh" => Shell::Zsh,
+            "fish" => Shell::Fish,
+            "nu" => Shell::Nu,
+            "powershell" => Shell::PowerShell,
+            _ => Shell::Bash,
+        }
+    }
+}
+
+/// Quote `s` as a single literal word for `shell`.
+pub fn quote(shell: Shell, s: &str) -> String {
+    match shell {
+        Shell::Bash | Shell::Zsh => shell_quote(s),
+        Shell::Fish => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        // raw strings take anything but their own terminator
+        Shell::Nu => format!("r#'{}'#", s),
+        Shell::PowerShell => format!("'{}'", s.replace('\'', "''")),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub completions: bool,
+    pub command_not_found: bool,
+}
+
+/// Prepend the bin dir to `PATH` unless it is already there, so sourcing
+/// the script twice does no harm.
+fn path_setup(shell: Shell, bin_dir: &str) -> String {
+    let dir = quote(shell, bin_dir);
+    match shell {
+        Shell::Bash | Shell::Zsh => format!(
+            "case \":$PATH:\" in\n  *:{dir}:*) ;;\n  *) export PATH={dir}\"${{PATH:+:$PATH}}\" ;;\nesac\n"
+        ),
+        Shell::Fish => format!("if not contains -- {dir} $PATH\n    set -gx PATH {dir} $PATH\nend\n"),
+        Shell::Nu => format!(
+            "$env.PATH = ($env.PATH | split row (char esep) | where $it != {dir} | prepend {dir})\n"
+        ),
+        Shell::PowerShell => format!(
+            "if (-not (($env:PATH -split ':') -contains {dir})) {{\n    $env:PATH = {dir} + ':' + $env:PATH\n}}\n"
+        ),
+    }
+}
+
+/// `bingo` as a command in `shell`, including `--home` if needed.
+fn invocation(shell: Shell, bingo: &[String]) -> String {
+    let words = bingo
+        .iter()
+        .map(|arg| quote(shell, arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    match shell {
+        Shell::Nu => format!("run-external {}", words),
+        Shell::PowerShell => format!("& {}", words),
+        _ => words,
+    }
+}
+
+/// Completion calling back into `bingo __complete`, which answers from the
+/// registry. A first line `:delegate N` hands the words from N on to the
+/// completion of the wrapped tool.
+pub fn completion_script(shell: Shell, bingo: &[String]) -> String {
+    let bingo = invocation(shell, bingo);
+    match shell {
+        Shell::Bash => format!(
+            r#"_bingo() {{
+    local IFS=$'\n' reply
+    reply=($({bingo} __complete -- "${{COMP_WORDS[@]:1:COMP_CWORD}}"))
+    if [[ ${{reply[0]}} == :delegate* ]]; then
+        local offset=$(( ${{reply[0]#:delegate }} + 1 ))
+        if declare -F _command_offset >/dev/null; then
+            _command_offset "$offset"
+        else
+            COMPREPLY=($(compgen -f -- "${{COMP_WORDS[COMP_CWORD]}}"))
+        fi
+        return
+    fi
+    COMPREPLY=("${{reply[@]}}")
+    if [[ ${{#COMPREPLY[@]}} -eq 1 && ${{COMPREPLY[0]}} == */ ]]; then
+        compopt -o nospace 2>/dev/null
+    fi
+}}
+complete -F _bingo bingo
+"#
+        ),
+        Shell::Zsh => format!(
+            r#"_bingo() {{
+    local -a reply dirs
+    reply=(${{(f)"$({bingo} __complete -- "${{(@)words[2,CURRENT]}}")"}})
+    if [[ $reply[1] == :delegate* ]]; then
+        local offset=$(( ${{reply[1]#:delegate }} + 1 ))
+        shift $offset words
+        (( CURRENT -= offset ))
+        _normal
+        return
+    fi
+    dirs=(${{(M)reply:#*/}})
+    compadd -- ${{reply:#*/}}
+    compadd -S '' -- $dirs
+}}
+(( $+functions[compdef] )) && compdef _bingo bingo
+"#
+        ),
+        Shell::Fish => format!(
+            r#"function __bingo_complete
+    set -l tokens (commandline -opc) (commandline -ct)
+    set -l reply ({bingo} __complete -- $tokens[2..-1])
+    if string match -q ':delegate *' -- $reply[1]
+        set -l offset (math (string replace ':delegate ' '' -- $reply[1]) + 2)
+        complete -C (string join ' ' -- (string escape -- $tokens[$offset..-2]) $tokens[-1])
+        return
+    end
+    printf '%s\n' $reply
+end
+complete -c bingo -f -a '(__bingo_complete)'
+"#
+        ),
+        Shell::Nu => format!(
+            r#"def "nu-complete bingo" [context: string] {{
+    let words = ($context | split row -r '\s+' | skip 1)
+    let reply = ({bingo} __complete -- ...$words | lines)
+    if ($reply | is-not-empty) and ($reply | first | str starts-with ':delegate ') {{
+        let offset = ($reply | first | str replace ':delegate ' '' | into int)
+        let completer = ($env.config.completions.external.completer? | default null)
+        if $completer == null {{ return [] }}
+        return (do $completer ($words | skip $offset))
+    }}
+    $reply
+}}
+export extern "bingo" [...args: string@"nu-complete bingo"]
+"#
+        ),
+        Shell::PowerShell => format!(
+            r#"Register-ArgumentCompleter -Native -CommandName bingo -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object {{ $_.ToString() }})
+    if ($wordToComplete -eq '') {{ $words += '' }}
+    $reply = @({bingo} __complete -- @words)
+    if ($reply.Count -gt 0 -and $reply[0] -like ':delegate *') {{
+        $offset = [int]($reply[0] -replace ':delegate ', '')
+        $line = ($words | Select-Object -Skip $offset) -join ' '
+        return [System.Management.Automation.CommandCompletion]::CompleteInput($line, $line.Length, $null).CompletionMatches
+    }}
+    $reply | ForEach-Object {{ [System.Management.Automation.CompletionResult]::new($_) }}
+}}
+"#
+        ),
+    }
+}
+
+/// Run unknown commands through bingo if they are registered, e.g. before
+/// the bin dir is on `PATH`. Disabled ones are still refused by `run`.
+fn command_not_found_hook(shell: Shell, bingo: &[String]) -> String {
+    let bingo = invocation(shell, bingo);
+    match shell {
+        Shell::Bash => format!(
+            "command_not_found_handle() {{\n    if {bingo} info \"$1\" >/dev/null 2>&1; then\n        {bingo} run -- \"$@\"\n        return\n    fi\n    printf 'bash: %s: command not found\\n' \"$1\" >&2\n    return 127\n}}\n"
+        ),
+        Shell::Zsh => format!(
+            "command_not_found_handler() {{\n    if {bingo} info \"$1\" >/dev/null 2>&1; then\n        {bingo} run -- \"$@\"\n        return\n    fi\n    printf 'zsh: command not found: %s\\n' \"$1\" >&2\n    return 127\n}}\n"
+        ),
+        Shell::Fish => format!(
+            "function fish_command_not_found\n    if {bingo} info $argv[1] >/dev/null 2>&1\n        {bingo} run -- $argv\n    else\n        __fish_default_command_not_found_handler $argv\n    end\nend\n"
+        ),
+        // nushell only lets the hook return a message
+        Shell::Nu => format!(
+            "$env.config = ($env.config | upsert hooks.command_not_found {{|name|\n    if (do {{ {bingo} info $name }} | complete | get exit_code) == 0 {{\n        $\"($name) is registered in bingo, run `bingo ($name)`\"\n    }}\n}})\n"
+        ),
+        Shell::PowerShell => format!(
+            "$ExecutionContext.InvokeCommand.CommandNotFoundAction = {{\n    param($name, $eventArgs)\n    {bingo} info $name *> $null\n    if ($LASTEXITCODE -eq 0) {{\n        $eventArgs.CommandScriptBlock = {{ {bingo} run -- $name @args }}.GetNewClosure()\n        $eventArgs.StopSearch = $true\n    }}\n}}\n"
+        ),
+    }
+}
+
+/// The script `bingo init <shell>` prints, to be evaluated by the shell's
+/// startup file.
+pub fn init_script(
+    shell: Shell,
+    paths: &BingoPaths,
+    options: &InitOptions,
+    bingo: &[String],
+) -> String {
+    let mut script = path_setup(shell, &paths.bin_dir);
+    if options.completions {
+        script.push_str(&completion_script(shell, bingo));
+    }
+    if options.command_not_found {
+        script.push_str(&command_not_found_hook(shell, bingo));
+    }
+    script
+}
+
+/// Where the bin dir is in the current `PATH`.
+pub struct PathCheck {
+    /// 1-based position among the `PATH` entries, `None` if missing.
+    pub position: Option<usize>,
+    pub entries: usize,
+    /// Registered names found in a directory before the bin dir, which
+    /// therefore win over bingo's entry.
+    pub shadowed: Vec<(String, String)>,
+}
+
+fn same_dir(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.trim_end_matches('/') == b.trim_end_matches('/'),
+    }
+}
+
+pub fn check_path(path: &str, bin_dir: &str, names: &[&str]) -> PathCheck {
+    let entries = path.split(':').collect::<Vec<_>>();
+    let position = entries.iter().position(|dir| same_dir(dir, bin_dir));
+    let before = match position {
+        Some(position) => &entries[..position],
+        None => &entries[..0],
+    };
+    let mut shadowed = vec![];
+    for name in names {
+        let found = before
+            .iter()
+            .find(|dir| !dir.is_empty() && Path::new(dir).join(name).is_file());
+        if let Some(dir) = found {
+            shadowed.push((name.to_string(), dir.to_string()));
+        }
+    }
+    PathCheck {
+        position: position.map(|p| p + 1),
+        entries: entries.len(),
+        shadowed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `PATH` entries `a`, `bin` and `b` in a fresh directory, with `tool`
+    /// in `a` and `other` in `b`.
+    fn dirs(name: &str) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("bingo-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a", "bin", "b"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("a/tool"), "").unwrap();
+        std::fs::write(root.join("b/other"), "").unwrap();
+        root
+    }
+
+    #[test]
+    fn check_path_finds_the_bin_dir_and_shadowed_names() {
+        let root = dirs("check-path");
+        let dir = |d: &str| root.join(d).to_str().unwrap().to_string();
+        let path = format!("{}:{}/:{}", dir("a"), dir("bin"), dir("b"));
+
+        let check = check_path(&path, &dir("bin"), &["tool", "other", "missing"]);
+        assert_eq!(check.position, Some(2));
+        assert_eq!(check.entries, 3);
+        assert_eq!(check.shadowed, [("tool".to_string(), dir("a"))]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn check_path_without_the_bin_dir() {
+        let root = dirs("check-path-missing");
+        let dir = |d: &str| root.join(d).to_str().unwrap().to_string();
+        let path = format!("{}:{}", dir("a"), dir("b"));
+
+        let check = check_path(&path, &dir("bin"), &["tool"]);
+        assert_eq!(check.position, None);
+        assert_eq!(check.entries, 2);
+        assert!(check.shadowed.is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+use crate::config::{Executable, ExecutableType};
+use crate::metadata::{self, sha256_file, Metadata};
+use crate::paths::BingoPaths;
+use std::path::Path;
+
+/// How an installed copy compares to its source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Freshness {
+    UpToDate,
+    /// The content is the same, but the source's mtime moved, the recorded
+    /// one should be refreshed so it is not hashed again.
+    Touched,
+    Changed,
+    SourceGone,
+}
+
+/// A `Binary` install, unversioned or one version of an executable.
+pub struct InstalledCopy<'a> {
+    /// `name`, or `name@label` for a version.
+    pub name: String,
+    pub executable: &'a str,
+    pub label: Option<&'a str>,
+    pub source: &'a str,
+    pub metadata: &'a Metadata,
+}
+
+/// The copies made for `e`. Links follow their source by themselves, and
+/// adopted files are their own source, so neither is listed.
+pub fn copies<'a>(e: &'a Executable, paths: &BingoPaths) -> Vec<InstalledCopy<'a>> {
+    if e.versions.is_empty() {
+        let is_copy = matches!(e.executable_type, ExecutableType::Binary)
+            && Path::new(&e.path) != Path::new(&paths.entry_file(&e.name, e.disabled));
+        if !is_copy {
+            return vec![];
+        }
+        return vec![InstalledCopy {
+            name: e.name.clone(),
+            executable: &e.name,
+            label: None,
+            source: &e.path,
+            metadata: &e.metadata,
+        }];
+    }
+    e.versions
+        .iter()
+        .filter(|v| matches!(v.executable_type, ExecutableType::Binary))
+        .map(|v| InstalledCopy {
+            name: format!("{}@{}", e.name, v.label),
+            executable: &e.name,
+            label: Some(&v.label),
+            source: &v.path,
+            metadata: &v.metadata,
+        })
+        .collect()
+}
+
+impl InstalledCopy<'_> {
+    /// Compare the source with what was installed. The source is only
+    /// hashed if its mtime or size changed, so a `touch` alone does not
+    /// count.
+    pub fn freshness(&self) -> Freshness {
+        let source = match std::fs::metadata(self.source) {
+            Ok(source) if source.is_file() => source,
+            _ => return Freshness::SourceGone,
+        };
+        let mtime = metadata::mtime(&source);
+        let recorded = self
+            .metadata
+            .source_mtime
+            .zip(self.metadata.source_mtime_nanos);
+        if mtime.is_some() && mtime == recorded && Some(source.len()) == self.metadata.size {
+            return Freshness::UpToDate;
+        }
+        match (&self.metadata.sha256, sha256_file(self.source)) {
+            (Some(installed), Ok(source)) if *installed == source => Freshness::Touched,
+            _ => Freshness::Changed,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::config::{Executable, ExecutableType};
+use crate::error::{BingoError, BingoResult};
+use crate::metadata::{sha256_file, Metadata};
+use crate::paths::BingoPaths;
+
+/// An installed file and the metadata recorded when it was installed.
+pub struct InstalledFile<'a> {
+    /// `name`, or `name@label` for a version.
+    pub name: String,
+    pub file: String,
+    pub metadata: &'a Metadata,
+}
+
+/// Result of re-hashing an installed file.
+pub enum Verified {
+    Ok,
+    /// Installed before checksums were recorded and never backfilled.
+    NoChecksum,
+}
+
+/// The files installed for `e`, one per version. Aliases have none.
+pub fn installed_files<'a>(e: &'a Executable, paths: &BingoPaths) -> Vec<InstalledFile<'a>> {
+    if matches!(e.executable_type, ExecutableType::Alias) {
+        return vec![];
+    }
+    if e.versions.is_empty() {
+        return vec![InstalledFile {
+            name: e.name.clone(),
+            file: paths.entry_file(&e.name, e.disabled),
+            metadata: &e.metadata,
+        }];
+    }
+    e.versions
+        .iter()
+        .map(|v| InstalledFile {
+            name: format!("{}@{}", e.name, v.label),
+            file: paths.version_file(&e.name, &v.label),
+            metadata: &v.metadata,
+        })
+        .collect()
+}
+
+impl InstalledFile<'_> {
+    /// Re-hash the file, a link is followed to its target.
+    pub fn verify(&self) -> BingoResult<Verified> {
+        let expected = match &self.metadata.sha256 {
+            Some(expected) => expected,
+            None => return Ok(Verified::NoChecksum),
+        };
+        let actual = sha256_file(&self.file)?;
+        if &actual != expected {
+            return Err(BingoError::ChecksumMismatch {
+                name: self.name.clone(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+        Ok(Verified::Ok)
+    }
+}
+
+/// Check the installed file of `e`, version `label` or the active one, and
+/// return it so strict mode runs exactly what was checked. Aliases have no
+/// file of their own.
+pub fn verify_before_run(
+    e: &Executable,
+    label: Option<&str>,
+    paths: &BingoPaths,
+) -> BingoResult<Option<String>> {
+    let label = label.or(e.version.as_deref());
+    let name = match label {
+        Some(label) => format!("{}@{}", e.name, label),
+        None => e.name.clone(),
+    };
+    for file in installed_files(e, paths) {
+        if file.name == name {
+            file.verify()?;
+            return Ok(Some(file.file));
+        }
+    }
+    Ok(None)
+}
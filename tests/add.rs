@@ -0,0 +1,78 @@
+mod common;
+
+use common::{bingo, command, registry, temp_dir, write_script};
+
+#[test]
+fn relative_paths_are_stored_normalized() {
+    let dir = temp_dir("add-normalized");
+    write_script(&dir.join("tool"), "hi");
+    assert!(bingo(&dir, &["ln", "."]).status.success());
+
+    let expected = dir.join("tool");
+    assert_eq!(
+        registry(&dir)["executables"][0]["path"],
+        expected.to_str().unwrap()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn command_names_are_not_taken_as_names() {
+    let dir = temp_dir("add-commands");
+    std::fs::create_dir(dir.join("path")).unwrap();
+    write_script(&dir.join("path/ls"), "hi");
+    write_script(&dir.join("path/cat"), "hi");
+    let output = command(&dir)
+        .env("PATH", dir.join("path"))
+        .args(["ln", "ls", "cat"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let names = registry(&dir)["executables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ls", "cat"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_name_follows_an_existing_file() {
+    let dir = temp_dir("add-explicit");
+    write_script(&dir.join("tool"), "hi");
+    assert!(bingo(&dir, &["ln", "./tool", "mytool"]).status.success());
+    assert!(bingo(&dir, &["ln", "tool", "yourtool"]).status.success());
+    assert!(bingo(&dir, &["ln", "tool", "--name", "other"])
+        .status
+        .success());
+
+    let doc = registry(&dir);
+    assert_eq!(doc["executables"][0]["name"], "mytool");
+    assert_eq!(doc["executables"][1]["name"], "yourtool");
+    assert_eq!(doc["executables"][2]["name"], "other");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn non_utf8_file_names_are_skipped() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = temp_dir("add-non-utf8");
+    std::fs::create_dir(dir.join("d")).unwrap();
+    write_script(&dir.join("d/tool"), "hi");
+    write_script(
+        &dir.join("d").join(std::ffi::OsStr::from_bytes(b"x\xff")),
+        "bad",
+    );
+    let output = bingo(&dir, &["ln", "d"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not valid UTF-8"), "{}", stderr);
+    let executables = registry(&dir)["executables"].as_array().unwrap().clone();
+    assert_eq!(executables.len(), 1);
+    assert_eq!(executables[0]["name"], "tool");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,41 @@
+//! Helpers shared by the integration tests. Each test works in its own
+//! directory, with the bingo home in `home` inside it.
+#![allow(dead_code)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh directory for the test `name`.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bingo-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// bingo running in `dir`, before its arguments are added.
+pub fn command(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_bingo"));
+    command.current_dir(dir).arg("--home").arg(dir.join("home"));
+    command
+}
+
+pub fn bingo(dir: &Path, args: &[&str]) -> Output {
+    command(dir).args(args).output().unwrap()
+}
+
+/// A shell script printing `output`.
+pub fn write_script(path: &Path, output: &str) {
+    std::fs::write(path, format!("#!/bin/sh\necho {}\n", output)).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+pub fn registry(dir: &Path) -> serde_json::Value {
+    let config = std::fs::read_to_string(dir.join("home/bingo.json")).unwrap();
+    serde_json::from_str(&config).unwrap()
+}
+
+pub fn write_registry(dir: &Path, doc: &serde_json::Value) {
+    std::fs::write(dir.join("home/bingo.json"), doc.to_string()).unwrap();
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{bingo, registry, temp_dir, write_registry, write_script};
+use std::path::Path;
+
+#[test]
+fn completion_does_not_warn_about_bad_entries() {
+    let dir = temp_dir("complete-quiet");
+    assert!(bingo(&dir, &["ls"]).status.success());
+    let mut doc = registry(&dir);
+    doc["executables"] = serde_json::json!([{ "name": 5 }]);
+    write_registry(&dir, &doc);
+
+    let output = bingo(&dir, &["__complete", "--", "run", ""]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    let output = bingo(&dir, &["ls"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("skipping entry"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// `node` with versions 18 and 20, and `samtools` tagged `bio`.
+fn registered(name: &str) -> std::path::PathBuf {
+    let dir = temp_dir(name);
+    for file in ["node18", "node20", "samtools"] {
+        write_script(&dir.join(file), file);
+    }
+    for args in [
+        &["ln", "./node18", "node", "--version", "18"][..],
+        &["ln", "./node20", "node", "--version", "20"],
+        &["ln", "./samtools"],
+        &["tag", "add", "samtools", "bio"],
+    ] {
+        assert!(bingo(&dir, args).status.success());
+    }
+    dir
+}
+
+/// The lines `bingo __complete -- <words>` prints.
+fn complete(dir: &Path, words: &[&str]) -> Vec<String> {
+    let mut args = vec!["__complete", "--"];
+    args.extend(words);
+    let output = bingo(dir, &args);
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+#[test]
+fn completes_names_versions_tags_and_options() {
+    let dir = registered("complete-words");
+    assert_eq!(complete(&dir, &["s"]), ["search", "samtools"]);
+    assert_eq!(complete(&dir, &["rm", ""]), ["node", "samtools"]);
+    assert_eq!(complete(&dir, &["use", "node", ""]), ["18", "20"]);
+    assert_eq!(complete(&dir, &["ls", "--tag", ""]), ["bio"]);
+    assert_eq!(complete(&dir, &["ls", "--t"]), ["--type", "--tag"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn delegates_the_arguments_of_registered_executables() {
+    let dir = registered("complete-delegate");
+    assert_eq!(complete(&dir, &["samtools", "view", "-"]), [":delegate 0"]);
+    assert_eq!(complete(&dir, &["run", "samtools", ""]), [":delegate 1"]);
+    assert_eq!(complete(&dir, &["r", "node", ""]), [":delegate 1"]);
+    assert_eq!(
+        complete(&dir, &["run", "--version", "20", "node", "x"]),
+        [":delegate 3"]
+    );
+    assert_eq!(
+        complete(&dir, &["--home", "home", "node", ""]),
+        [":delegate 2"]
+    );
+    // not registered, nothing to delegate to
+    assert_eq!(complete(&dir, &["nosuch", ""]), Vec::<String>::new());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
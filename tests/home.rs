@@ -0,0 +1,23 @@
+mod common;
+
+use common::temp_dir;
+use std::process::Command;
+
+#[test]
+fn a_later_home_flag_leaves_the_default_home_alone() {
+    let dir = temp_dir("home-later-flag");
+    let output = Command::new(env!("CARGO_BIN_EXE_bingo"))
+        .env("HOME", dir.join("user"))
+        .env_remove("BINGO_HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .arg("ls")
+        .arg("--home")
+        .arg(dir.join("home"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(dir.join("home/bingo.json").exists());
+    assert!(!dir.join("user/.bingo").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
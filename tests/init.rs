@@ -0,0 +1,40 @@
+mod common;
+
+use common::{bingo, command, temp_dir, write_script};
+
+#[test]
+fn check_reports_the_position_and_shadowed_names() {
+    let dir = temp_dir("init-check");
+    write_script(&dir.join("tool"), "hi");
+    assert!(bingo(&dir, &["cp", "./tool"]).status.success());
+    std::fs::create_dir(dir.join("early")).unwrap();
+    write_script(&dir.join("early/tool"), "other");
+    let bin_dir = dir.join("home/bin");
+    let path = format!(
+        "{}:{}:/usr/bin",
+        dir.join("early").display(),
+        bin_dir.display()
+    );
+
+    let output = command(&dir)
+        .env("PATH", &path)
+        .args(["init", "--check"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let expected = format!(
+        "{} is entry 2 of 3 on PATH.\nwarning: tool is shadowed by {}/tool\n",
+        bin_dir.display(),
+        dir.join("early").display()
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+
+    let output = command(&dir)
+        .env("PATH", "/usr/bin")
+        .args(["init", "--check"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is not on PATH"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,56 @@
+use bingogo::config::{migrate_json, CONFIG_SCHEMA_VERSION};
+
+fn migrate(json: &str) -> serde_json::Value {
+    let migrated = migrate_json(json).unwrap().unwrap();
+    serde_json::from_str(&migrated.json).unwrap()
+}
+
+#[test]
+fn migrates_to_the_current_schema() {
+    let doc = migrate(r#"{"version":"0.2.1","executables":[]}"#);
+    assert_eq!(doc["version"], CONFIG_SCHEMA_VERSION.to_string());
+}
+
+#[test]
+fn v3_gains_settings() {
+    let doc = migrate(r#"{"version":"3","executables":[]}"#);
+    assert_eq!(doc["settings"], serde_json::json!({ "strict": false }));
+}
+
+#[test]
+fn v3_keeps_existing_settings() {
+    let doc = migrate(r#"{"version":"3","settings":{"strict":true},"executables":[]}"#);
+    assert_eq!(doc["settings"]["strict"], true);
+}
+
+#[test]
+fn v4_gains_tags() {
+    let doc = migrate(r#"{"version":"4","executables":[{"name":"a"},{"name":"b","tags":["x"]}]}"#);
+    assert_eq!(doc["executables"][0]["tags"], serde_json::json!([]));
+    assert_eq!(doc["executables"][1]["tags"], serde_json::json!(["x"]));
+}
+
+#[test]
+fn v5_marks_executables_enabled() {
+    let doc =
+        migrate(r#"{"version":"5","executables":[{"name":"a"},{"name":"b","disabled":true}]}"#);
+    assert_eq!(doc["executables"][0]["disabled"], false);
+    assert_eq!(doc["executables"][1]["disabled"], true);
+}
+
+#[test]
+fn v6_keeps_the_default_name_rule() {
+    let doc = migrate(r#"{"version":"6","settings":{"strict":true},"executables":[]}"#);
+    assert_eq!(doc["version"], CONFIG_SCHEMA_VERSION.to_string());
+    assert_eq!(doc["settings"], serde_json::json!({ "strict": true }));
+}
+
+#[test]
+fn v7_leaves_source_mtimes_alone() {
+    let doc =
+        migrate(r#"{"version":"7","executables":[{"name":"a","metadata":{"source_mtime":1}}]}"#);
+    assert_eq!(
+        doc["executables"][0]["metadata"],
+        serde_json::json!({ "source_mtime": 1 })
+    );
+}
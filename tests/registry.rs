@@ -0,0 +1,62 @@
+mod common;
+
+use common::{bingo, registry, temp_dir, write_script};
+
+#[test]
+fn a_malformed_entry_is_reported_with_its_location() {
+    let dir = temp_dir("registry-located");
+    write_script(&dir.join("tool"), "hi");
+    assert!(bingo(&dir, &["ln", "./tool"]).status.success());
+    let mut doc = registry(&dir);
+    doc["executables"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({ "name": 5 }));
+    let json = serde_json::to_string_pretty(&doc).unwrap();
+    std::fs::write(dir.join("home/bingo.json"), &json).unwrap();
+    let line = json
+        .lines()
+        .position(|l| l.contains("\"name\": 5"))
+        .unwrap()
+        + 1;
+
+    let output = bingo(&dir, &["ls"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let location = format!("executables[1].name (line {}, column ", line);
+    assert!(stderr.contains(&location), "{}", stderr);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("tool"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn doctor_lists_a_dangling_link() {
+    let dir = temp_dir("registry-doctor");
+    write_script(&dir.join("tool"), "hi");
+    assert!(bingo(&dir, &["ln", "./tool"]).status.success());
+    std::fs::remove_file(dir.join("tool")).unwrap();
+
+    let output = bingo(&dir, &["doctor"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("broken link: tool ->"), "{}", stdout);
+    assert!(stdout.contains("1 problem(s) found"), "{}", stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn adopt_registers_an_unmanaged_bin_entry() {
+    let dir = temp_dir("registry-adopt");
+    assert!(bingo(&dir, &["ls"]).status.success());
+    let stray = dir.join("home/bin/stray");
+    write_script(&stray, "stray");
+
+    assert!(bingo(&dir, &["adopt", "stray"]).status.success());
+    let doc = registry(&dir);
+    assert_eq!(doc["executables"][0]["name"], "stray");
+    assert_eq!(doc["executables"][0]["path"], stray.to_str().unwrap());
+    assert_eq!(doc["executables"][0]["executable_type"], "Binary");
+    let output = bingo(&dir, &["stray"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "stray\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::{bingo, temp_dir, write_script};
+use std::path::{Path, PathBuf};
+
+/// A copy of a script printing `good`, registered as `tool` in strict mode.
+fn strict_copy(dir: &Path) -> PathBuf {
+    let source = dir.join("tool");
+    write_script(&source, "good");
+    assert!(bingo(dir, &["cp", "./tool"]).status.success());
+    assert!(bingo(dir, &["config", "set", "strict", "true"])
+        .status
+        .success());
+    source
+}
+
+#[test]
+fn strict_refuses_a_tampered_install() {
+    let dir = temp_dir("strict-tampered");
+    strict_copy(&dir);
+    write_script(&dir.join("home/bin/tool"), "bad");
+
+    let output = bingo(&dir, &["tool"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("checksum mismatch"), "{}", stderr);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn strict_runs_the_verified_file_not_the_source() {
+    let dir = temp_dir("strict-source");
+    let source = strict_copy(&dir);
+    write_script(&source, "bad");
+
+    let output = bingo(&dir, &["run", "tool"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "good\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
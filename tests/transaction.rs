@@ -0,0 +1,47 @@
+mod common;
+
+use bingogo::config::BingoConfigFile;
+use bingogo::error::BingoError;
+use bingogo::paths::BingoPaths;
+use common::temp_dir;
+
+#[test]
+fn a_failed_transaction_restores_the_bin_dir() {
+    let dir = temp_dir("transaction-rollback");
+    let paths = BingoPaths::from_home(dir.join("home").to_str().unwrap());
+    let mut config_file = BingoConfigFile::new(paths);
+    config_file.init().unwrap();
+    config_file.load().unwrap();
+    let bin = |name: &str| dir.join("home/bin").join(name);
+    std::fs::write(bin("removed"), "removed").unwrap();
+    std::fs::write(bin("replaced"), "old").unwrap();
+    std::fs::write(bin("renamed"), "renamed").unwrap();
+
+    let result = config_file.transaction(|_, txn| {
+        txn.remove(bin("removed").to_str().unwrap())?;
+        let replaced = bin("replaced");
+        txn.create(replaced.to_str().unwrap(), || {
+            std::fs::write(&replaced, "new").unwrap();
+            Ok(())
+        })?;
+        let (from, to) = (bin("renamed"), bin("moved"));
+        txn.rename(from.to_str().unwrap(), to.to_str().unwrap())?;
+        txn.create_dir(dir.join("home/a/b/c").to_str().unwrap())?;
+        Err::<(), _>(BingoError::FileSystemError("failed".to_string()))
+    });
+    assert!(result.is_err());
+
+    let read = |name: &str| std::fs::read_to_string(bin(name)).unwrap();
+    assert_eq!(read("removed"), "removed");
+    assert_eq!(read("replaced"), "old");
+    assert_eq!(read("renamed"), "renamed");
+    assert!(!bin("moved").exists());
+    assert!(!dir.join("home/a").exists());
+    let mut entries = std::fs::read_dir(dir.join("home/bin"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(entries, ["removed", "renamed", "replaced"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
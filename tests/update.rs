@@ -0,0 +1,70 @@
+mod common;
+
+use common::{bingo, registry, temp_dir, write_script};
+use std::path::Path;
+
+/// A copy of a script printing `one`, registered as `tool`.
+fn copied(dir: &Path) {
+    write_script(&dir.join("tool"), "one");
+    assert!(bingo(dir, &["cp", "./tool"]).status.success());
+}
+
+fn outdated(dir: &Path) -> (Option<i32>, String) {
+    let output = bingo(dir, &["outdated"]);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    (output.status.code(), stdout)
+}
+
+#[test]
+fn an_untouched_source_is_up_to_date() {
+    let dir = temp_dir("update-fresh");
+    copied(&dir);
+    assert_eq!(
+        outdated(&dir),
+        (Some(0), "Everything is up to date.\n".to_string())
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_rebuild_within_the_same_second_is_stale() {
+    let dir = temp_dir("update-stale");
+    copied(&dir);
+    // same size, and most likely the same second
+    write_script(&dir.join("tool"), "two");
+    assert_eq!(outdated(&dir), (Some(1), "changed: tool\n".to_string()));
+
+    assert!(bingo(&dir, &["update", "tool"]).status.success());
+    let output = bingo(&dir, &["tool"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "two\n");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_missing_source_is_reported() {
+    let dir = temp_dir("update-missing");
+    copied(&dir);
+    std::fs::remove_file(dir.join("tool")).unwrap();
+    let (code, stdout) = outdated(&dir);
+    assert_eq!(code, Some(1));
+    assert!(stdout.starts_with("source gone: tool"), "{}", stdout);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_touched_source_gets_its_mtime_recorded() {
+    let dir = temp_dir("update-touched");
+    copied(&dir);
+    let source = std::fs::File::options()
+        .write(true)
+        .open(dir.join("tool"))
+        .unwrap();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    source.set_modified(later).unwrap();
+
+    assert_eq!(outdated(&dir).0, Some(0));
+    let recorded = registry(&dir)["executables"][0]["metadata"]["source_mtime"].clone();
+    let later = later.duration_since(std::time::UNIX_EPOCH).unwrap();
+    assert_eq!(recorded, later.as_secs());
+    std::fs::remove_dir_all(&dir).unwrap();
+}